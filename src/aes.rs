@@ -0,0 +1,331 @@
+//! AES-128 block cipher modes (ECB, CBC, CTR) and PKCS#7 padding built on the set1 helpers
+
+use set1::helper;
+use set1::helper::aes128lookup;
+use storage::Storage;
+
+use std::collections::HashSet;
+
+/// BLOCK -- size of an AES block in bytes
+pub const BLOCK: usize = 16;
+
+/// Mode -- block cipher mode of operation
+/// Ecb - every block is encrypted on its own
+/// Cbc - every block is XOR'd w/ the previous ciphertext block (or the iv) before encryption
+/// Ctr - the cipher encrypts nonce || counter and the result is XOR'd w/ the data
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    Ecb,
+    Cbc,
+    Ctr,
+}
+
+impl Mode {
+    /// parse -- mode from its command line name
+    /// Parameters: name (&str) - ecb, cbc or ctr
+    /// Return: Mode - matching mode
+    pub fn parse(name: &str) -> Mode {
+        match name {
+            "ecb" => Mode::Ecb,
+            "cbc" => Mode::Cbc,
+            "ctr" => Mode::Ctr,
+            _ => panic!("Error: invalid mode ({})", name),
+        }
+    }
+}
+
+/// EcbLine -- line of a file ranked by how likely it is to be ECB encrypted
+/// line (usize) - line number in the file (starting at 1)
+/// repeats (usize) - number of blocks that repeat an earlier block
+/// blocks (usize) - number of whole blocks in the line
+pub struct EcbLine {
+    pub line: usize,
+    pub repeats: usize,
+    pub blocks: usize,
+}
+
+/// raw -- copy of a storage as raw bytes, whatever its encoding was
+fn raw(s: &Storage) -> Storage {
+    if s.get_data_type() == "ascii" {
+        return Storage::new_init_vec(s.get_data(), "ascii");
+    }
+    let mut copy = Storage::new_init_vec(s.get_data(), s.get_data_type());
+    copy.change_base("ascii");
+    copy
+}
+
+/// check_key -- panic unless the key is 16 raw bytes
+fn check_key(key: &Storage) -> Storage {
+    let key = raw(key);
+    if key.len() != BLOCK {
+        panic!("Error: AES-128 keys are 16 bytes, this key is {} bytes", key.len());
+    }
+    key
+}
+
+/// pkcs7_pad -- pad a message to a multiple of the block size
+/// A message that is already a multiple gets a whole block of padding.
+/// Parameters: data (&Storage) - message
+///             block (usize) - block size (1 - 255)
+/// Return: Storage - padded message (ascii)
+pub fn pkcs7_pad(data: &Storage, block: usize) -> Storage {
+    if block == 0 || block > 255 {
+        panic!("Error: PKCS#7 block size must be between 1 and 255");
+    }
+    let mut out = raw(data).get_data().clone();
+    let n = block - out.len() % block;
+    out.extend(vec![n as u8; n]);
+    Storage::new_init_vec(&out, "ascii")
+}
+
+/// pkcs7_valid -- true when the message ends in valid PKCS#7 padding
+/// the message must be whole blocks and the padding at most one block
+/// Parameters: data (&Storage) - padded message
+///             block (usize) - block size the message was padded to
+/// Return: bool - padding is valid
+pub fn pkcs7_valid(data: &Storage, block: usize) -> bool {
+    let data = data.get_data();
+    let n = match data.last() {
+        Some(n) => *n as usize,
+        None => return false,
+    };
+    data.len().is_multiple_of(block)
+        && n >= 1
+        && n <= block
+        && data[data.len() - n..].iter().all(|b| *b as usize == n)
+}
+
+/// pkcs7_unpad -- strip PKCS#7 padding
+/// Parameters: data (&Storage) - padded message
+///             block (usize) - block size the message was padded to
+/// Return: Storage - message w/o padding (ascii)
+pub fn pkcs7_unpad(data: &Storage, block: usize) -> Storage {
+    let data = raw(data);
+    if !pkcs7_valid(&data, block) {
+        panic!("Error: invalid PKCS#7 padding");
+    }
+    let n = *data.get_data().last().unwrap() as usize;
+    Storage::new_init_vec(&data.get_data()[..data.len() - n], "ascii")
+}
+
+/// ecb_encrypt -- AES-128 in ECB mode (no padding is added)
+/// Parameters: data (&Storage) - plaintext (multiple of 16 bytes)
+///             key (&Storage) - 16 byte key
+/// Return: Storage - ciphertext (ascii)
+pub fn ecb_encrypt(data: &Storage, key: &Storage) -> Storage {
+    helper::cipher_aes_128(&raw(data), &check_key(key))
+}
+
+/// ecb_decrypt -- AES-128 in ECB mode (padding is left in place)
+/// Parameters: data (&Storage) - ciphertext (multiple of 16 bytes)
+///             key (&Storage) - 16 byte key
+/// Return: Storage - plaintext (ascii)
+pub fn ecb_decrypt(data: &Storage, key: &Storage) -> Storage {
+    helper::inv_cipher_aes_128(&raw(data), &check_key(key))
+}
+
+/// cbc_encrypt -- AES-128 in CBC mode (no padding is added)
+/// Parameters: data (&Storage) - plaintext (multiple of 16 bytes)
+///             key (&Storage) - 16 byte key
+///             iv (&Storage) - 16 byte initialization vector
+/// Return: Storage - ciphertext (ascii)
+pub fn cbc_encrypt(data: &Storage, key: &Storage, iv: &Storage) -> Storage {
+    let data = raw(data);
+    let iv = raw(iv);
+    if !data.len().is_multiple_of(BLOCK) {
+        panic!("Error: the length of the data must be divisible by 16");
+    }
+    if iv.len() != BLOCK {
+        panic!("Error: the iv must be 16 bytes, this iv is {} bytes", iv.len());
+    }
+    let (s_box, _, rcon, _, _, _, _) = aes128lookup::get_aes_128_lookup_tables();
+    let keys = helper::calc_key_expansion(&check_key(key), &s_box, &rcon);
+
+    let mut out: Vec<u8> = Vec::new();
+    let mut previous = iv;
+    for block in data.get_data().chunks(BLOCK) {
+        let block = &Storage::new_init_vec(block, "ascii") ^ &previous;
+        previous = helper::cipher_aes_128_block(&block, &keys, &s_box);
+        out.extend_from_slice(previous.get_data());
+    }
+    Storage::new_init_vec(&out, "ascii")
+}
+
+/// cbc_decrypt -- AES-128 in CBC mode (padding is left in place)
+/// Parameters: data (&Storage) - ciphertext (multiple of 16 bytes)
+///             key (&Storage) - 16 byte key
+///             iv (&Storage) - 16 byte initialization vector
+/// Return: Storage - plaintext (ascii)
+pub fn cbc_decrypt(data: &Storage, key: &Storage, iv: &Storage) -> Storage {
+    let data = raw(data);
+    let iv = raw(iv);
+    if !data.len().is_multiple_of(BLOCK) {
+        panic!("Error: the length of the data must be divisible by 16");
+    }
+    if iv.len() != BLOCK {
+        panic!("Error: the iv must be 16 bytes, this iv is {} bytes", iv.len());
+    }
+    let (s_box, inverse_s_box, rcon, mul_9, mul_11, mul_13, mul_14) = aes128lookup::get_aes_128_lookup_tables();
+    let keys = helper::calc_key_expansion(&check_key(key), &s_box, &rcon);
+
+    let mut out: Vec<u8> = Vec::new();
+    let mut previous = iv;
+    for block in data.get_data().chunks(BLOCK) {
+        let block = Storage::new_init_vec(block, "ascii");
+        let state = helper::inv_cipher_aes_128_block(&block, &keys, &inverse_s_box, &mul_9, &mul_11, &mul_13, &mul_14);
+        out.extend_from_slice((&state ^ &previous).get_data());
+        previous = block;
+    }
+    Storage::new_init_vec(&out, "ascii")
+}
+
+/// ctr -- AES-128 in CTR mode (encryption and decryption are the same operation)
+/// The keystream is AES(key, nonce || counter) w/ an 8 byte nonce and a 64 bit little endian
+/// counter that starts at 0.
+/// Parameters: data (&Storage) - plaintext or ciphertext (any length)
+///             key (&Storage) - 16 byte key
+///             nonce (u64) - nonce (written little endian)
+/// Return: Storage - ciphertext or plaintext (ascii)
+pub fn ctr(data: &Storage, key: &Storage, nonce: u64) -> Storage {
+    let data = raw(data);
+    let (s_box, _, rcon, _, _, _, _) = aes128lookup::get_aes_128_lookup_tables();
+    let keys = helper::calc_key_expansion(&check_key(key), &s_box, &rcon);
+
+    let mut out: Vec<u8> = Vec::new();
+    for (counter, block) in data.get_data().chunks(BLOCK).enumerate() {
+        let mut input = nonce.to_le_bytes().to_vec();
+        input.extend_from_slice(&(counter as u64).to_le_bytes());
+        let keystream = helper::cipher_aes_128_block(&Storage::new_init_vec(&input, "ascii"), &keys, &s_box);
+        out.extend(block.iter().zip(keystream.get_data().iter()).map(|(b, k)| b ^ k));
+    }
+    Storage::new_init_vec(&out, "ascii")
+}
+
+/// repeated_blocks -- count the blocks that are equal to an earlier block.  ECB encrypts equal
+/// plaintext blocks to equal ciphertext blocks, random looking data almost never repeats.
+/// Parameters: data (&Storage) - ciphertext (any data type)
+///             block (usize) - block size
+/// Return: usize - number of repeated blocks
+pub fn repeated_blocks(data: &Storage, block: usize) -> usize {
+    let data = raw(data);
+    let blocks: Vec<&[u8]> = data.get_data().chunks(block).filter(|b| b.len() == block).collect();
+    let mut seen: HashSet<&[u8]> = HashSet::new();
+    blocks.iter().filter(|b| !seen.insert(b)).count()
+}
+
+/// detect_ecb -- rank the lines of a file by the number of repeated 16 byte blocks
+/// Parameters: lines (&[Storage]) - one ciphertext per line (any data type)
+/// Return: Vec<EcbLine> - every non empty line, most repeats first
+pub fn detect_ecb(lines: &[Storage]) -> Vec<EcbLine> {
+    let mut out: Vec<EcbLine> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(i, l)| EcbLine {
+            line: i + 1,
+            repeats: repeated_blocks(l, BLOCK),
+            blocks: raw(l).len() / BLOCK,
+        })
+        .collect();
+    out.sort_by(|l, r| r.repeats.cmp(&l.repeats).then(l.line.cmp(&r.line)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Storage {
+        let mut s = Storage::new_init(s, "hex");
+        s.change_base("ascii");
+        s
+    }
+
+    fn to_hex(s: &Storage) -> String {
+        let mut s = Storage::new_init_vec(s.get_data(), "ascii");
+        s.change_base("hex");
+        s.to_string()
+    }
+
+    // TEST aes
+    #[test]
+    fn check_fips_197() {
+        // FIPS-197 appendix C.1
+        let key = hex("000102030405060708090a0b0c0d0e0f");
+        let plaintext = hex("00112233445566778899aabbccddeeff");
+        let ciphertext = ecb_encrypt(&plaintext, &key);
+        assert_eq!("69c4e0d86a7b0430d8cdb78070b4c55a", to_hex(&ciphertext));
+        assert_eq!("00112233445566778899aabbccddeeff", to_hex(&ecb_decrypt(&ciphertext, &key)));
+    }
+
+    #[test]
+    fn check_cbc() {
+        // NIST SP 800-38A F.2.1
+        let key = hex("2b7e151628aed2a6abf7158809cf4f3c");
+        let iv = hex("000102030405060708090a0b0c0d0e0f");
+        let plaintext = hex("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51");
+        let ciphertext = cbc_encrypt(&plaintext, &key, &iv);
+        assert_eq!(
+            "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2",
+            to_hex(&ciphertext)
+        );
+        assert_eq!(to_hex(&plaintext), to_hex(&cbc_decrypt(&ciphertext, &key, &iv)));
+    }
+
+    #[test]
+    fn check_ctr() {
+        // set 3 challenge 18
+        let mut ciphertext = Storage::new_init(
+            "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==",
+            "base64",
+        );
+        ciphertext.change_base("ascii");
+        let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+        let plaintext = ctr(&ciphertext, &key, 0);
+        assert_eq!("Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby ", plaintext.to_string());
+        assert_eq!(to_hex(&ciphertext), to_hex(&ctr(&plaintext, &key, 0)));
+    }
+
+    #[test]
+    fn check_detect_ecb() {
+        let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+        let plaintext = Storage::new_init(&"A".repeat(64), "ascii");
+        let cbc = cbc_encrypt(&plaintext, &key, &Storage::new_init("0123456789abcdef", "ascii"));
+        let ecb = ecb_encrypt(&plaintext, &key);
+        let lines = vec![cbc, Storage::new(), ecb];
+        assert_eq!(3, repeated_blocks(&lines[2], BLOCK));
+
+        let ranked = detect_ecb(&lines);
+        assert_eq!(2, ranked.len());
+        assert_eq!(3, ranked[0].line);
+        assert_eq!(3, ranked[0].repeats);
+        assert_eq!(4, ranked[0].blocks);
+        assert_eq!(0, ranked[1].repeats);
+    }
+
+    #[test]
+    fn check_pkcs7() {
+        let padded = pkcs7_pad(&Storage::new_init("YELLOW SUBMARINE", "ascii"), 20);
+        assert_eq!("YELLOW SUBMARINE\x04\x04\x04\x04", padded.to_string());
+        assert_eq!("YELLOW SUBMARINE", pkcs7_unpad(&padded, 20).to_string());
+        assert_eq!(32, pkcs7_pad(&Storage::new_init("YELLOW SUBMARINE", "ascii"), 16).len());
+        assert!(!pkcs7_valid(&Storage::new_init("ICE ICE BABY\x05\x05\x05\x05", "ascii"), 16));
+        assert!(!pkcs7_valid(&Storage::new_init("ICE ICE BABY\x01\x02\x03\x04", "ascii"), 16));
+        assert!(!pkcs7_valid(&Storage::new_init("ICE ICE BABY\x00", "ascii"), 16));
+    }
+
+    #[test]
+    fn check_pkcs7_valid_block() {
+        assert!(pkcs7_valid(&Storage::new_init("ICE ICE BABY\x04\x04\x04\x04", "ascii"), 16));
+        // padding longer than a block, or a message that isn't whole blocks
+        assert!(!pkcs7_valid(&Storage::new_init_vec(&[0x11; 17], "ascii"), 16));
+        assert!(!pkcs7_valid(&Storage::new_init_vec(&[0x20; 32], "ascii"), 16));
+        assert!(!pkcs7_valid(&Storage::new_init("ICE ICE BABY\x01", "ascii"), 16));
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_pkcs7_unpad_two_blocks() {
+        pkcs7_unpad(&Storage::new_init_vec(&[0x20; 32], "ascii"), 16);
+    }
+}
@@ -0,0 +1,318 @@
+//! statistics used to triage unknown data
+
+use storage::Storage;
+
+use std::collections::HashMap; // hashmap used to group repeated substrings
+
+/// Repeat -- a substring that appears more than once (Kasiski examination)
+/// sequence (Vec<u8>) - repeated bytes
+/// positions (Vec<usize>) - start index of every occurrence in ascending order
+pub struct Repeat {
+    pub sequence: Vec<u8>,
+    pub positions: Vec<usize>,
+}
+
+/// histogram -- count how many times each byte value appears
+/// Parameters: s (&Storage) - data (ascii)
+/// Return: Vec<u64> - 256 counts indexed by byte value
+pub fn histogram(s: &Storage) -> Vec<u64> {
+    let mut counts = vec![0u64; 256];
+    for d in s.get_data() {
+        counts[*d as usize] += 1;
+    }
+    counts
+}
+
+/// entropy -- shannon entropy of the data
+/// 0.0 for constant data, 8.0 for uniformly random bytes
+/// Parameters: s (&Storage) - data (ascii)
+/// Return: f64 - entropy in bits per byte
+pub fn entropy(s: &Storage) -> f64 {
+    if s.is_empty() {
+        return 0.0;
+    }
+    let n = s.len() as f64;
+    histogram(s)
+        .iter()
+        .filter(|c| **c > 0)
+        .map(|c| {
+            let p = *c as f64 / n;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// sliding_entropy -- shannon entropy of every window of the data
+/// useful to find compressed or encrypted regions inside a larger file
+/// Parameters: s (&Storage) - data (ascii)
+///             window (usize) - number of bytes in each window
+///             step (usize) - number of bytes between the start of each window
+/// Return: Vec<(usize, f64)> - (start index, entropy) of each window
+pub fn sliding_entropy(s: &Storage, window: usize, step: usize) -> Vec<(usize, f64)> {
+    if window == 0 || step == 0 {
+        panic!("Error: window and step must be greater than 0");
+    }
+    if s.len() < window {
+        return vec![(0, entropy(s))];
+    }
+
+    (0..=(s.len() - window))
+        .step_by(step)
+        .map(|i| (i, entropy(&s.index(i, i + window))))
+        .collect()
+}
+
+/// index_of_coincidence -- probability that two bytes picked at random are equal
+/// english text is around 0.065, uniformly random bytes are around 1/256
+/// Parameters: s (&Storage) - data (ascii)
+/// Return: f64 - index of coincidence
+pub fn index_of_coincidence(s: &Storage) -> f64 {
+    let n = s.len() as u64;
+    if n < 2 {
+        return 0.0;
+    }
+    let matches: u64 = histogram(s).iter().map(|c| c * c.saturating_sub(1)).sum();
+    matches as f64 / (n * (n - 1)) as f64
+}
+
+/// period_index_of_coincidence -- index of coincidence of every nth byte averaged over the
+/// n columns.  The period that matches the key length of a polyalphabetic cipher stands out.
+/// Parameters: s (&Storage) - data (ascii)
+///             period (usize) - distance between bytes in the same column
+/// Return: f64 - average index of coincidence of the columns
+pub fn period_index_of_coincidence(s: &Storage, period: usize) -> f64 {
+    if period == 0 {
+        panic!("Error: period must be greater than 0");
+    }
+    if s.len() < 2 * period {
+        return 0.0;
+    }
+
+    let total: f64 = (0..period)
+        .map(|column| {
+            let d: Vec<u8> = s.get_data().iter().skip(column).step_by(period).cloned().collect();
+            index_of_coincidence(&Storage::new_init_vec(&d, "ascii"))
+        })
+        .sum();
+    total / period as f64
+}
+
+/// chi_squared_uniform -- chi-squared statistic of the byte histogram against a uniform
+/// distribution.  Random data is close to 255 (the degrees of freedom), text is much larger.
+/// Parameters: s (&Storage) - data (ascii)
+/// Return: f64 - chi-squared statistic
+pub fn chi_squared_uniform(s: &Storage) -> f64 {
+    if s.is_empty() {
+        return 0.0;
+    }
+    let expected = s.len() as f64 / 256.0;
+    histogram(s)
+        .iter()
+        .map(|c| {
+            let diff = *c as f64 - expected;
+            diff * diff / expected
+        })
+        .sum()
+}
+
+/// repeated_substrings -- find every substring of length that appears more than once
+/// Parameters: s (&Storage) - data (ascii)
+///             length (usize) - length of the substrings (3 is the classic kasiski choice)
+/// Return: Vec<Repeat> - repeated substrings, most frequent first
+pub fn repeated_substrings(s: &Storage, length: usize) -> Vec<Repeat> {
+    if length == 0 {
+        panic!("Error: length must be greater than 0");
+    }
+
+    let mut seen: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    for (i, w) in s.get_data().windows(length).enumerate() {
+        seen.entry(w).or_default().push(i);
+    }
+
+    let mut out: Vec<Repeat> = seen
+        .into_iter()
+        .filter(|(_, p)| p.len() > 1)
+        .map(|(w, p)| Repeat {
+            sequence: w.to_vec(),
+            positions: p,
+        })
+        .collect();
+    out.sort_by(|l, r| {
+        r.positions
+            .len()
+            .cmp(&l.positions.len())
+            .then(l.positions[0].cmp(&r.positions[0]))
+    });
+    out
+}
+
+/// kasiski_factors -- count how many distances between repeated substrings each key length
+/// divides.  The key length of a repeating-key cipher divides most of the distances.
+/// Parameters: repeats (&[Repeat]) - output of repeated_substrings
+///             max (usize) - largest key length to count (included)
+/// Return: Vec<(usize, usize)> - (key length, number of distances) sorted most common first
+pub fn kasiski_factors(repeats: &Vec<Repeat>, max: usize) -> Vec<(usize, usize)> {
+    let mut counts = vec![0usize; max + 1];
+    for r in repeats {
+        for p in r.positions.windows(2) {
+            let distance = p[1] - p[0];
+            for (k, count) in counts.iter_mut().enumerate().skip(2) {
+                if distance % k == 0 {
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    let mut out: Vec<(usize, usize)> = counts
+        .into_iter()
+        .enumerate()
+        .skip(2)
+        .filter(|(_, c)| *c > 0)
+        .collect();
+    out.sort_by(|l, r| r.1.cmp(&l.1).then(l.0.cmp(&r.0)));
+    out
+}
+
+/// print_report -- print every statistic in this module for the data
+/// Parameters: s (&Storage) - data (ascii)
+/// Return: void
+pub fn print_report(s: &Storage) {
+    println!("Size: {} bytes", s.len());
+    println!("Entropy: {:.4} bits per byte", entropy(s));
+    println!("Chi-squared vs uniform: {:.2} (255 degrees of freedom)", chi_squared_uniform(s));
+    println!("Index of coincidence: {:.5}", index_of_coincidence(s));
+
+    let counts = histogram(s);
+    let mut common: Vec<(usize, u64)> = counts
+        .iter()
+        .cloned()
+        .enumerate()
+        .filter(|(_, c)| *c > 0)
+        .collect();
+    common.sort_by(|l, r| r.1.cmp(&l.1).then(l.0.cmp(&r.0)));
+    println!("Distinct bytes: {}", common.len());
+    println!("Most common bytes:");
+    for (b, c) in common.iter().take(10) {
+        println!(
+            "  0x{:02x} {:?} {} ({:.2}%)",
+            b,
+            *b as u8 as char,
+            c,
+            100.0 * *c as f64 / s.len() as f64
+        );
+    }
+
+    let window = 256;
+    let windows = sliding_entropy(s, window, window);
+    let min = windows.iter().map(|w| w.1).fold(f64::INFINITY, f64::min);
+    let max = windows.iter().map(|w| w.1).fold(0.0, f64::max);
+    println!("Sliding entropy ({} byte windows): min {:.4}, max {:.4}", window, min, max);
+
+    println!("Index of coincidence by period:");
+    for period in 1..=40 {
+        if s.len() < 2 * period {
+            break;
+        }
+        println!("  {:>2}: {:.5}", period, period_index_of_coincidence(s, period));
+    }
+
+    let repeats = repeated_substrings(s, 3);
+    println!("Repeated 3-byte substrings: {}", repeats.len());
+    for r in repeats.iter().take(10) {
+        let mut sequence = Storage::new_init_vec(&r.sequence, "ascii");
+        sequence.change_base("hex");
+        println!("  {} at {:?}", sequence, r.positions);
+    }
+    println!("Kasiski key length factors:");
+    for (k, c) in kasiski_factors(&repeats, 40).iter().take(10) {
+        println!("  {:>2}: {}", k, c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &str = "It was the best of times, it was the worst of times, it was the age \
+        of wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch \
+        of incredulity, it was the season of Light, it was the season of Darkness, it was the \
+        spring of hope, it was the winter of despair.";
+
+    // TEST histogram
+    #[test]
+    fn check_histogram() {
+        let counts = histogram(&Storage::new_init("hello", "ascii"));
+        assert_eq!(256, counts.len());
+        assert_eq!(2, counts[b'l' as usize]);
+        assert_eq!(1, counts[b'h' as usize]);
+        assert_eq!(0, counts[b'z' as usize]);
+    }
+
+    // TEST entropy
+    #[test]
+    fn check_entropy() {
+        assert_eq!(0.0, entropy(&Storage::new_init("aaaaaaaa", "ascii")));
+        assert_eq!(1.0, entropy(&Storage::new_init("abababab", "ascii")));
+        assert_eq!(2.0, entropy(&Storage::new_init("abcdabcd", "ascii")));
+
+        let all: Vec<u8> = (0u8..=255u8).collect();
+        assert_eq!(8.0, entropy(&Storage::new_init_vec(&all, "ascii")));
+    }
+
+    #[test]
+    fn check_sliding_entropy() {
+        let s = Storage::new_init("aaaaabcdabcd", "ascii");
+        let windows = sliding_entropy(&s, 4, 4);
+        assert_eq!(3, windows.len());
+        assert_eq!((0, 0.0), windows[0]);
+        assert_eq!((8, 2.0), windows[2]);
+    }
+
+    // TEST index_of_coincidence, period_index_of_coincidence
+    #[test]
+    fn check_index_of_coincidence() {
+        assert_eq!(1.0, index_of_coincidence(&Storage::new_init("aaaa", "ascii")));
+        assert_eq!(0.0, index_of_coincidence(&Storage::new_init("abcd", "ascii")));
+        assert!(index_of_coincidence(&Storage::new_init(PLAINTEXT, "ascii")) > 0.05);
+    }
+
+    #[test]
+    fn check_period_index_of_coincidence() {
+        let cipher = &Storage::new_init(PLAINTEXT, "ascii") ^ &Storage::new_init("KEY", "ascii");
+        let ic3 = period_index_of_coincidence(&cipher, 3);
+        assert!(ic3 > period_index_of_coincidence(&cipher, 2));
+        assert!(ic3 > period_index_of_coincidence(&cipher, 4));
+        assert_eq!(
+            index_of_coincidence(&Storage::new_init(PLAINTEXT, "ascii")),
+            period_index_of_coincidence(&Storage::new_init(PLAINTEXT, "ascii"), 1)
+        );
+    }
+
+    // TEST chi_squared_uniform
+    #[test]
+    fn check_chi_squared_uniform() {
+        let all: Vec<u8> = (0u8..=255u8).collect();
+        assert_eq!(0.0, chi_squared_uniform(&Storage::new_init_vec(&all, "ascii")));
+        assert!(chi_squared_uniform(&Storage::new_init(PLAINTEXT, "ascii")) > 1000.0);
+    }
+
+    // TEST repeated_substrings, kasiski_factors
+    #[test]
+    fn check_repeated_substrings() {
+        let repeats = repeated_substrings(&Storage::new_init("abcxxabcyyabc", "ascii"), 3);
+        assert_eq!(1, repeats.len());
+        assert_eq!(b"abc".to_vec(), repeats[0].sequence);
+        assert_eq!(vec![0, 5, 10], repeats[0].positions);
+    }
+
+    #[test]
+    fn check_kasiski_factors() {
+        let cipher = &Storage::new_init(PLAINTEXT, "ascii") ^ &Storage::new_init("KEYS", "ascii");
+        let factors = kasiski_factors(&repeated_substrings(&cipher, 3), 20);
+        let count = |k: usize| factors.iter().find(|f| f.0 == k).map_or(0, |f| f.1);
+        assert!(count(4) > count(3));
+        assert!(count(4) > count(5));
+        assert!(count(4) > count(6));
+    }
+}
@@ -0,0 +1,322 @@
+//! time challenges over many runs and compare them w/ a saved baseline
+
+use challenge::{Challenge, Overrides};
+use report::json_string;
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Bench -- wall time of every run of a single challenge
+/// set (u32) - set the challenge belongs to
+/// id (u32) - challenge number
+/// title (&str) - challenge title
+/// runs (Vec<Duration>) - time of each run, sorted from fastest to slowest
+/// bytes (usize) - raw bytes of input processed by each run
+pub struct Bench {
+    pub set: u32,
+    pub id: u32,
+    pub title: &'static str,
+    pub runs: Vec<Duration>,
+    pub bytes: usize,
+}
+
+/// Baseline -- times of a challenge saved by an earlier bench run
+/// set (u32) - set the challenge belongs to
+/// id (u32) - challenge number
+/// median_ms (f64) - median wall time in milliseconds
+pub struct Baseline {
+    pub set: u32,
+    pub id: u32,
+    pub median_ms: f64,
+}
+
+/// Comparison -- a challenge measured now and in the baseline
+/// set (u32) - set the challenge belongs to
+/// id (u32) - challenge number
+/// baseline_ms (f64) - median wall time in the baseline
+/// median_ms (f64) - median wall time now
+/// ratio (f64) - median_ms / baseline_ms (above 1.0 is slower)
+pub struct Comparison {
+    pub set: u32,
+    pub id: u32,
+    pub baseline_ms: f64,
+    pub median_ms: f64,
+    pub ratio: f64,
+}
+
+impl Comparison {
+    /// regressed -- true when the challenge got slower by more than the tolerance
+    /// Parameters: tolerance (f64) - allowed slow down (0.2 allows 20%)
+    /// Return: bool - slower than the baseline allows
+    pub fn regressed(&self, tolerance: f64) -> bool {
+        self.ratio > 1.0 + tolerance
+    }
+}
+
+impl Bench {
+    pub fn min(&self) -> Duration {
+        self.runs[0]
+    }
+
+    pub fn max(&self) -> Duration {
+        self.runs[self.runs.len() - 1]
+    }
+
+    /// median -- middle run (the mean of the two middle runs for an even count)
+    pub fn median(&self) -> Duration {
+        let n = self.runs.len();
+        if n % 2 == 1 {
+            self.runs[n / 2]
+        } else {
+            (self.runs[n / 2 - 1] + self.runs[n / 2]) / 2
+        }
+    }
+
+    /// throughput -- input bytes processed per second at the median time
+    /// Return: f64 - bytes per second (0.0 when the median rounds to nothing)
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.median().as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.bytes as f64 / seconds
+    }
+}
+
+/// bench -- run a challenge several times w/ its canned inputs
+/// Parameters: c (&dyn Challenge) - challenge to time
+///             runs (usize) - number of runs (at least 1)
+/// Return: Bench - every run time and the size of the input
+pub fn bench(c: &dyn Challenge, runs: usize) -> Bench {
+    if runs == 0 {
+        panic!("Error: bench needs at least one run");
+    }
+
+    let overrides = Overrides::new();
+    let mut times: Vec<Duration> = Vec::new();
+    let mut bytes = 0;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let outcome = c.run_with(&overrides);
+        times.push(start.elapsed());
+
+        if bytes == 0 {
+            let mut input = outcome.get_input();
+            if input.get_data_type() != "ascii" {
+                input.change_base("ascii");
+            }
+            bytes = input.len();
+        }
+    }
+    times.sort();
+
+    Bench {
+        set: c.set(),
+        id: c.id(),
+        title: c.title(),
+        runs: times,
+        bytes,
+    }
+}
+
+/// human_rate -- bytes per second w/ a binary unit
+fn human_rate(rate: f64) -> String {
+    let units = ["B/s", "KiB/s", "MiB/s", "GiB/s"];
+    let mut rate = rate;
+    let mut unit = 0;
+    while rate >= 1024.0 && unit < units.len() - 1 {
+        rate /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", rate, units[unit])
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// print_table -- one row per challenge w/ min/median/max and throughput, followed by the
+/// change against the baseline when one is given
+/// Parameters: benches (&[Bench]) - results to print
+///             comparisons (&[Comparison]) - results compared w/ the baseline
+///             tolerance (f64) - allowed slow down before a challenge is marked SLOWER
+/// Return: void
+pub fn print_table(benches: &[Bench], comparisons: &[Comparison], tolerance: f64) {
+    let width = benches.iter().map(|b| b.title.len()).max().unwrap_or(0).max(5);
+    println!(
+        "{:<9} {:<width$} {:>4} {:>10} {:>10} {:>10} {:>12}  Baseline",
+        "Challenge", "Title", "Runs", "Min (ms)", "Median", "Max", "Throughput",
+        width = width
+    );
+    for b in benches {
+        let baseline = match comparisons.iter().find(|c| c.set == b.set && c.id == b.id) {
+            Some(c) => format!(
+                "{:+.1}%{}",
+                (c.ratio - 1.0) * 100.0,
+                if c.regressed(tolerance) { " SLOWER" } else { "" }
+            ),
+            None => "-".to_owned(),
+        };
+        println!(
+            "{:<9} {:<width$} {:>4} {:>10.3} {:>10.3} {:>10.3} {:>12}  {}",
+            format!("{}-{}", b.set, b.id),
+            b.title,
+            b.runs.len(),
+            millis(b.min()),
+            millis(b.median()),
+            millis(b.max()),
+            human_rate(b.throughput()),
+            baseline,
+            width = width
+        );
+    }
+}
+
+/// to_json -- results as a baseline document (one challenge per line)
+/// Parameters: benches (&[Bench]) - results to write
+/// Return: String - {"challenges": [{"set", "id", "title", "runs", "min_ms", "median_ms", ...}]}
+pub fn to_json(benches: &[Bench]) -> String {
+    let challenges: Vec<String> = benches
+        .iter()
+        .map(|b| {
+            format!(
+                "    {{\"set\": {}, \"id\": {}, \"title\": {}, \"runs\": {}, \"min_ms\": {:.3}, \
+                 \"median_ms\": {:.3}, \"max_ms\": {:.3}, \"bytes\": {}, \"bytes_per_sec\": {:.1}}}",
+                b.set,
+                b.id,
+                json_string(b.title),
+                b.runs.len(),
+                millis(b.min()),
+                millis(b.median()),
+                millis(b.max()),
+                b.bytes,
+                b.throughput()
+            )
+        })
+        .collect();
+    format!("{{\n  \"challenges\": [\n{}\n  ]\n}}\n", challenges.join(",\n"))
+}
+
+/// json_number -- value of a numeric key in a single line JSON object
+fn json_number(line: &str, key: &str) -> Option<f64> {
+    let pattern = format!("\"{}\":", key);
+    let start = line.find(&pattern)? + pattern.len();
+    let value: String = line[start..]
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == '-' || *c == 'e' || *c == 'E')
+        .collect();
+    value.parse::<f64>().ok()
+}
+
+/// parse_baseline -- read a document written by to_json
+/// Only the set, id and median of each challenge are needed, so the lines are scanned for
+/// those keys instead of parsing the whole document.
+/// Parameters: contents (&str) - baseline document
+/// Return: Vec<Baseline> - one entry per challenge
+pub fn parse_baseline(contents: &str) -> Vec<Baseline> {
+    contents
+        .lines()
+        .filter_map(|l| {
+            Some(Baseline {
+                set: json_number(l, "set")? as u32,
+                id: json_number(l, "id")? as u32,
+                median_ms: json_number(l, "median_ms")?,
+            })
+        })
+        .collect()
+}
+
+/// load_baseline -- read a baseline file
+pub fn load_baseline(filename: &str) -> Vec<Baseline> {
+    parse_baseline(&fs::read_to_string(filename).expect("Error: Unable to read baseline"))
+}
+
+/// save_baseline -- write the results as a baseline file
+pub fn save_baseline(filename: &str, benches: &[Bench]) {
+    fs::write(filename, to_json(benches)).expect("Error: Unable to write baseline");
+}
+
+/// compare -- match every result w/ its baseline (challenges missing from either are skipped)
+/// Parameters: benches (&[Bench]) - results now
+///             baseline (&[Baseline]) - results of an earlier run
+/// Return: Vec<Comparison> - one entry per challenge in both
+pub fn compare(benches: &[Bench], baseline: &[Baseline]) -> Vec<Comparison> {
+    benches
+        .iter()
+        .filter_map(|b| {
+            let base = baseline.iter().find(|l| l.set == b.set && l.id == b.id)?;
+            let median_ms = millis(b.median());
+            Some(Comparison {
+                set: b.set,
+                id: b.id,
+                baseline_ms: base.median_ms,
+                median_ms,
+                ratio: if base.median_ms > 0.0 { median_ms / base.median_ms } else { 1.0 },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use challenge;
+
+    fn fake(runs: Vec<u64>) -> Bench {
+        let mut runs: Vec<Duration> = runs.into_iter().map(Duration::from_millis).collect();
+        runs.sort();
+        Bench {
+            set: 1,
+            id: 3,
+            title: "fake \"title\"",
+            runs,
+            bytes: 1024,
+        }
+    }
+
+    // TEST bench
+    #[test]
+    fn check_bench() {
+        let c = challenge::find(1, 1).unwrap();
+        let b = bench(c.as_ref(), 3);
+        assert_eq!(3, b.runs.len());
+        assert_eq!(48, b.bytes);
+        assert!(b.min() <= b.median() && b.median() <= b.max());
+    }
+
+    #[test]
+    fn check_stats() {
+        let b = fake(vec![30, 10, 20]);
+        assert_eq!(Duration::from_millis(10), b.min());
+        assert_eq!(Duration::from_millis(20), b.median());
+        assert_eq!(Duration::from_millis(30), b.max());
+        assert_eq!(1024.0 / 0.02, b.throughput());
+        assert_eq!(Duration::from_millis(25), fake(vec![10, 20, 30, 40]).median());
+    }
+
+    // TEST to_json, parse_baseline, compare
+    #[test]
+    fn check_baseline_round_trip() {
+        let json = to_json(&[fake(vec![10, 20, 30])]);
+        assert!(json.contains("\"title\": \"fake \\\"title\\\"\""));
+        let baseline = parse_baseline(&json);
+        assert_eq!(1, baseline.len());
+        assert_eq!((1, 3), (baseline[0].set, baseline[0].id));
+        assert_eq!(20.0, baseline[0].median_ms);
+    }
+
+    #[test]
+    fn check_compare() {
+        let baseline = parse_baseline(&to_json(&[fake(vec![10, 20, 30])]));
+        let slower = compare(&[fake(vec![30, 30, 30])], &baseline);
+        assert_eq!(1, slower.len());
+        assert_eq!(1.5, slower[0].ratio);
+        assert!(slower[0].regressed(0.2));
+        assert!(!slower[0].regressed(0.5));
+
+        let mut other = fake(vec![30]);
+        other.id = 4;
+        assert!(compare(&[other], &baseline).is_empty());
+    }
+}
@@ -0,0 +1,426 @@
+//! attacks on AES-128 in CBC mode (bit flipping and padding oracles) and the oracles they are
+//! run against
+
+use aes::{self, BLOCK};
+use ecb::random_bytes;
+use storage::Storage;
+
+use rand::{thread_rng, Rng};
+
+use std::cell::Cell;
+
+/// flip_block -- previous ciphertext block that turns a known plaintext block into the desired
+/// one.  CBC XORs the previous ciphertext block into every decrypted block, so flipping a bit
+/// there flips the same bit of the plaintext (the previous block decrypts to garbage).
+/// Parameters: previous (&[u8]) - ciphertext block before the target block (or the iv)
+///             known (&[u8]) - plaintext of the target block
+///             desired (&[u8]) - plaintext the target block should decrypt to
+/// Return: Vec<u8> - modified previous block
+pub fn flip_block(previous: &[u8], known: &[u8], desired: &[u8]) -> Vec<u8> {
+    if previous.len() != known.len() || known.len() != desired.len() {
+        panic!("Error: bit flipping needs blocks of the same length");
+    }
+    previous
+        .iter()
+        .zip(known.iter().zip(desired.iter()))
+        .map(|(c, (k, d))| c ^ k ^ d)
+        .collect()
+}
+
+/// bit_flip -- ciphertext w/ a block that decrypts to the desired plaintext
+/// Parameters: cipher (&[u8]) - ciphertext (w/o the iv)
+///             target (usize) - index of the block to change (at least 1)
+///             known (&[u8]) - plaintext of the target block
+///             desired (&[u8]) - plaintext the target block should decrypt to
+/// Return: Vec<u8> - modified ciphertext
+pub fn bit_flip(cipher: &[u8], target: usize, known: &[u8], desired: &[u8]) -> Vec<u8> {
+    let block = known.len();
+    if target == 0 {
+        panic!("Error: the first block is flipped through the iv");
+    }
+    if (target + 1) * block > cipher.len() {
+        panic!("Error: block {} is past the end of the ciphertext", target);
+    }
+    let previous = (target - 1) * block..target * block;
+    let mut out = cipher.to_vec();
+    let flipped = flip_block(&cipher[previous.clone()], known, desired);
+    out[previous].copy_from_slice(&flipped);
+    out
+}
+
+/// CommentOracle -- encrypts the user data between comment1=cooking%20MCs;userdata= and
+/// ;comment2=%20like%20a%20pound%20of%20bacon under a fixed key and iv in CBC mode w/ PKCS#7
+/// padding, w/ ; and = in the user data quoted as %3B and %3D [challenge 16]
+/// key (Storage) - 16 byte key
+/// iv (Storage) - 16 byte iv
+pub struct CommentOracle {
+    key: Storage,
+    iv: Storage,
+}
+
+impl CommentOracle {
+    const PREFIX: &'static str = "comment1=cooking%20MCs;userdata=";
+    const SUFFIX: &'static str = ";comment2=%20like%20a%20pound%20of%20bacon";
+
+    pub fn new(key: Storage, iv: Storage) -> CommentOracle {
+        CommentOracle { key, iv }
+    }
+
+    /// random -- oracle w/ a random key and iv
+    pub fn random<R: Rng>(rng: &mut R) -> CommentOracle {
+        let key = Storage::new_init_vec(&random_bytes(rng, BLOCK), "ascii");
+        let iv = Storage::new_init_vec(&random_bytes(rng, BLOCK), "ascii");
+        CommentOracle::new(key, iv)
+    }
+
+    /// encrypt -- quote the user data and encrypt the comment
+    /// Parameters: userdata (&str) - attacker chosen data
+    /// Return: Vec<u8> - ciphertext (w/o the iv)
+    pub fn encrypt(&self, userdata: &str) -> Vec<u8> {
+        let quoted = userdata.replace("%", "%25").replace(";", "%3B").replace("=", "%3D");
+        let comment = format!("{}{}{}", CommentOracle::PREFIX, quoted, CommentOracle::SUFFIX);
+        let padded = aes::pkcs7_pad(&Storage::new_init(&comment, "ascii"), BLOCK);
+        aes::cbc_encrypt(&padded, &self.key, &self.iv).get_data().clone()
+    }
+
+    /// decrypt -- decrypt a comment and split it into k=v pairs on ;
+    /// Parameters: cipher (&[u8]) - ciphertext (w/o the iv)
+    /// Return: Vec<(Vec<u8>, Vec<u8>)> - keys and values in order (a pair w/o = gets an empty
+    ///         value, bytes are kept as they are since flipped blocks decrypt to garbage)
+    pub fn decrypt(&self, cipher: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let padded = aes::cbc_decrypt(&Storage::new_init_vec(cipher, "ascii"), &self.key, &self.iv);
+        aes::pkcs7_unpad(&padded, BLOCK)
+            .get_data()
+            .split(|b| *b == b';')
+            .map(|p| match p.iter().position(|b| *b == b'=') {
+                Some(i) => (p[..i].to_vec(), p[i + 1..].to_vec()),
+                None => (p.to_vec(), Vec::new()),
+            })
+            .collect()
+    }
+
+    /// is_admin -- true when the decrypted comment has admin=true
+    pub fn is_admin(&self, cipher: &[u8]) -> bool {
+        self.decrypt(cipher).iter().any(|(k, v)| k == b"admin" && v == b"true")
+    }
+}
+
+/// find_prefix_len -- length of the data a CBC oracle puts before the input.  The first block
+/// that changes w/ the input holds its first byte, and growing a filler until that block stops
+/// changing w/ the byte after the filler shows how much of the block the prefix takes.
+/// Parameters: oracle (&Fn(&str) -> Vec<u8>) - CBC encryption oracle w/ a fixed key and iv
+///             block (usize) - block size
+/// Return: usize - length of the prefix
+pub fn find_prefix_len<F: Fn(&str) -> Vec<u8>>(oracle: &F, block: usize) -> usize {
+    let first_change = |l: &[u8], r: &[u8]| {
+        (0..l.len().min(r.len()) / block)
+            .find(|i| l[i * block..(i + 1) * block] != r[i * block..(i + 1) * block])
+            .expect("Error: the input doesn't change the ciphertext")
+    };
+    let start = first_change(&oracle("A"), &oracle("B"));
+    for n in 1..block + 1 {
+        let filler = "A".repeat(n);
+        let l = oracle(&format!("{}X", filler));
+        let r = oracle(&format!("{}Y", filler));
+        if first_change(&l, &r) > start {
+            return (start + 1) * block - n;
+        }
+    }
+    panic!("Error: the ciphertext doesn't look like CBC w/ a fixed key and iv");
+}
+
+/// inject -- make a CBC oracle's ciphertext decrypt w/ the text in it, even though the oracle
+/// quotes the metacharacters.  The input pads the prefix to a whole block and adds a block to
+/// sacrifice and a block of filler, then the sacrificed block is flipped so the filler block
+/// decrypts to the text.
+/// Parameters: oracle (&Fn(&str) -> Vec<u8>) - CBC encryption oracle w/ a fixed key and iv
+///             text (&[u8]) - text to inject (at most a block, padded w/ 'A')
+/// Return: Vec<u8> - modified ciphertext
+pub fn inject<F: Fn(&str) -> Vec<u8>>(oracle: &F, text: &[u8]) -> Vec<u8> {
+    if text.len() > BLOCK {
+        panic!("Error: the injected text must fit in a block");
+    }
+    let prefix_len = find_prefix_len(oracle, BLOCK);
+    let pad = (BLOCK - prefix_len % BLOCK) % BLOCK;
+    let cipher = oracle(&"A".repeat(pad + 2 * BLOCK));
+
+    let known = vec![b'A'; BLOCK];
+    let mut desired = text.to_vec();
+    desired.resize(BLOCK, b'A');
+    bit_flip(&cipher, (prefix_len + pad) / BLOCK + 1, &known, &desired)
+}
+
+/// PaddingOracle -- encrypts under a fixed key in CBC mode w/ a random iv and only tells
+/// whether a ciphertext decrypts w/ valid PKCS#7 padding [challenge 17]
+/// key (Storage) - 16 byte key
+pub struct PaddingOracle {
+    key: Storage,
+}
+
+impl PaddingOracle {
+    pub fn new(key: Storage) -> PaddingOracle {
+        PaddingOracle { key }
+    }
+
+    /// random -- oracle w/ a random key
+    pub fn random<R: Rng>(rng: &mut R) -> PaddingOracle {
+        PaddingOracle::new(Storage::new_init_vec(&random_bytes(rng, BLOCK), "ascii"))
+    }
+
+    /// encrypt -- pad and encrypt w/ a random iv
+    /// Parameters: plain (&[u8]) - plaintext
+    /// Return: (Vec<u8>, Vec<u8>) - iv and ciphertext
+    pub fn encrypt(&self, plain: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let iv = random_bytes(&mut thread_rng(), BLOCK);
+        let padded = aes::pkcs7_pad(&Storage::new_init_vec(plain, "ascii"), BLOCK);
+        let cipher = aes::cbc_encrypt(&padded, &self.key, &Storage::new_init_vec(&iv, "ascii"));
+        (iv, cipher.get_data().clone())
+    }
+
+    /// decrypt -- decrypt and strip the padding
+    /// Parameters: iv (&[u8]) - 16 byte iv
+    ///             cipher (&[u8]) - ciphertext
+    /// Return: Option<Vec<u8>> - plaintext (None when the padding is invalid)
+    pub fn decrypt(&self, iv: &[u8], cipher: &[u8]) -> Option<Vec<u8>> {
+        let padded = aes::cbc_decrypt(
+            &Storage::new_init_vec(cipher, "ascii"),
+            &self.key,
+            &Storage::new_init_vec(iv, "ascii"),
+        );
+        if !aes::pkcs7_valid(&padded, BLOCK) {
+            return None;
+        }
+        Some(aes::pkcs7_unpad(&padded, BLOCK).get_data().clone())
+    }
+
+    /// padding_valid -- the only thing the oracle tells the attacker
+    pub fn padding_valid(&self, iv: &[u8], cipher: &[u8]) -> bool {
+        self.decrypt(iv, cipher).is_some()
+    }
+}
+
+/// PaddingAttack -- result of a padding oracle attack
+/// iv (Vec<u8>) - iv (forged when encrypting)
+/// cipher (Vec<u8>) - ciphertext (forged when encrypting)
+/// plain (Vec<u8>) - plaintext w/ its padding
+/// queries (usize) - calls made to the oracle
+pub struct PaddingAttack {
+    pub iv: Vec<u8>,
+    pub cipher: Vec<u8>,
+    pub plain: Vec<u8>,
+    pub queries: usize,
+}
+
+/// decrypt_block -- block cipher decryption of a ciphertext block (before the XOR w/ the
+/// previous block), found from the last byte back.  The forged iv sets the bytes after the
+/// current one to the padding value and every value of the current byte is tried until the
+/// padding is valid.  For the last byte a valid padding can also be \x02\x02 (or longer) when
+/// the byte before happens to match, so the byte before is changed and the guess is kept only
+/// when the padding stays valid.
+/// Parameters: oracle (&Fn(&[u8], &[u8]) -> bool) - padding oracle taking an iv and a ciphertext
+///             block (&[u8]) - ciphertext block
+/// Return: Vec<u8> - decrypted block before the XOR
+pub fn decrypt_block<F: Fn(&[u8], &[u8]) -> bool>(oracle: &F, block: &[u8]) -> Vec<u8> {
+    let size = block.len();
+    let mut inter = vec![0u8; size];
+    for pos in (0..size).rev() {
+        let pad = (size - pos) as u8;
+        let mut iv = vec![0u8; size];
+        for j in pos + 1..size {
+            iv[j] = inter[j] ^ pad;
+        }
+
+        let found = (0..256).map(|g| g as u8).find(|g| {
+            iv[pos] = *g;
+            if !oracle(&iv, block) {
+                return false;
+            }
+            if pos == 0 || pad > 1 {
+                return true;
+            }
+            // \x01 stays valid whatever the byte before is, \x02\x02 and longer don't
+            let mut check = iv.clone();
+            check[pos - 1] ^= 0xff;
+            oracle(&check, block)
+        });
+        match found {
+            Some(g) => inter[pos] = g ^ pad,
+            None => panic!("Error: the oracle accepted no padding for byte {} of the block", pos),
+        }
+    }
+    inter
+}
+
+/// padding_oracle_decrypt -- decrypt a CBC ciphertext w/ a padding oracle (no key needed)
+/// Parameters: oracle (&Fn(&[u8], &[u8]) -> bool) - padding oracle taking an iv and a ciphertext
+///             iv (&[u8]) - iv of the ciphertext
+///             cipher (&[u8]) - ciphertext (multiple of the block size)
+/// Return: PaddingAttack - plaintext w/ its padding and the number of queries
+pub fn padding_oracle_decrypt<F: Fn(&[u8], &[u8]) -> bool>(oracle: &F, iv: &[u8], cipher: &[u8]) -> PaddingAttack {
+    let size = iv.len();
+    if size == 0 || !cipher.len().is_multiple_of(size) {
+        panic!("Error: the ciphertext must be whole blocks of the iv size");
+    }
+    let queries = Cell::new(0);
+    let counted = |iv: &[u8], cipher: &[u8]| {
+        queries.set(queries.get() + 1);
+        oracle(iv, cipher)
+    };
+
+    let mut previous = iv;
+    let mut plain: Vec<u8> = Vec::new();
+    for block in cipher.chunks(size) {
+        let inter = decrypt_block(&counted, block);
+        plain.extend(inter.iter().zip(previous.iter()).map(|(i, p)| i ^ p));
+        previous = block;
+    }
+
+    PaddingAttack {
+        iv: iv.to_vec(),
+        cipher: cipher.to_vec(),
+        plain,
+        queries: queries.get(),
+    }
+}
+
+/// padding_oracle_encrypt -- forge a CBC ciphertext for a chosen plaintext w/ a padding
+/// oracle.  Starting from a random last block, decrypt_block shows what each block decrypts
+/// to, so the block before it (and finally the iv) is chosen to XOR that into the plaintext.
+/// Parameters: oracle (&Fn(&[u8], &[u8]) -> bool) - padding oracle taking an iv and a ciphertext
+///             plain (&[u8]) - plaintext (padding is added)
+///             block (usize) - block size
+/// Return: PaddingAttack - forged iv and ciphertext, padded plaintext and the number of queries
+pub fn padding_oracle_encrypt<F: Fn(&[u8], &[u8]) -> bool>(oracle: &F, plain: &[u8], block: usize) -> PaddingAttack {
+    let queries = Cell::new(0);
+    let counted = |iv: &[u8], cipher: &[u8]| {
+        queries.set(queries.get() + 1);
+        oracle(iv, cipher)
+    };
+
+    let padded = aes::pkcs7_pad(&Storage::new_init_vec(plain, "ascii"), block);
+    let padded = padded.get_data();
+    let mut blocks: Vec<Vec<u8>> = vec![random_bytes(&mut thread_rng(), block)];
+    for p in padded.chunks(block).rev() {
+        let inter = decrypt_block(&counted, &blocks[0]);
+        blocks.insert(0, inter.iter().zip(p.iter()).map(|(i, p)| i ^ p).collect());
+    }
+
+    let iv = blocks.remove(0);
+    PaddingAttack {
+        iv,
+        cipher: blocks.concat(),
+        plain: padded.clone(),
+        queries: queries.get(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // TEST flip_block, bit_flip
+    #[test]
+    fn check_bit_flip() {
+        let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+        let iv = Storage::new_init_vec(&[0; BLOCK], "ascii");
+        let plain = Storage::new_init("0123456789abcdef0123456789abcdef", "ascii");
+        let cipher = aes::cbc_encrypt(&plain, &key, &iv).get_data().clone();
+
+        let flipped = bit_flip(&cipher, 1, b"0123456789abcdef", b"0123456789ABCDEF");
+        assert_eq!(cipher[BLOCK..], flipped[BLOCK..]);
+        let out = aes::cbc_decrypt(&Storage::new_init_vec(&flipped, "ascii"), &key, &iv);
+        assert_eq!(b"0123456789ABCDEF", &out.get_data()[BLOCK..]);
+        assert_eq!(vec![3, 0, 7], flip_block(&[1, 2, 3], &[1, 1, 1], &[3, 3, 5]));
+    }
+
+    #[test]
+    #[should_panic(expected = "flipped through the iv")]
+    fn check_bit_flip_first_block() {
+        bit_flip(&[0; 32], 0, &[0; 16], &[1; 16]);
+    }
+
+    // TEST CommentOracle
+    #[test]
+    fn check_comment_oracle() {
+        let oracle = CommentOracle::random(&mut StdRng::seed_from_u64(16));
+        let pairs = oracle.decrypt(&oracle.encrypt("x;admin=true"));
+        assert_eq!(3, pairs.len());
+        assert_eq!(b"x%3Badmin%3Dtrue".to_vec(), pairs[1].1);
+        assert!(!oracle.is_admin(&oracle.encrypt(";admin=true;")));
+    }
+
+    // TEST find_prefix_len, inject
+    #[test]
+    fn check_find_prefix_len() {
+        let oracle = CommentOracle::random(&mut StdRng::seed_from_u64(17));
+        assert_eq!(32, find_prefix_len(&|u: &str| oracle.encrypt(u), BLOCK));
+
+        // any prefix length, not only whole blocks
+        for prefix in [0, 1, 15, 17].iter() {
+            let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+            let iv = Storage::new_init_vec(&[7; BLOCK], "ascii");
+            let encrypt = |u: &str| {
+                let data = format!("{}{};tail", "p".repeat(*prefix), u);
+                let padded = aes::pkcs7_pad(&Storage::new_init(&data, "ascii"), BLOCK);
+                aes::cbc_encrypt(&padded, &key, &iv).get_data().clone()
+            };
+            assert_eq!(*prefix, find_prefix_len(&encrypt, BLOCK));
+        }
+    }
+
+    #[test]
+    fn check_inject() {
+        let mut rng = StdRng::seed_from_u64(18);
+        for _ in 0..5 {
+            let oracle = CommentOracle::random(&mut rng);
+            let forged = inject(&|u: &str| oracle.encrypt(u), b";admin=true;");
+            assert!(oracle.is_admin(&forged));
+        }
+    }
+
+    // TEST padding_oracle_decrypt, padding_oracle_encrypt
+    #[test]
+    fn check_padding_oracle_decrypt() {
+        let oracle = PaddingOracle::random(&mut StdRng::seed_from_u64(19));
+        let check = |iv: &[u8], cipher: &[u8]| oracle.padding_valid(iv, cipher);
+        for plain in [&b""[..], b"YELLOW SUBMARINE", b"000003Cooking MC's like a pound of bacon"].iter() {
+            let (iv, cipher) = oracle.encrypt(plain);
+            let found = padding_oracle_decrypt(&check, &iv, &cipher);
+            let unpadded = aes::pkcs7_unpad(&Storage::new_init_vec(&found.plain, "ascii"), BLOCK);
+            assert_eq!(&plain[..], &unpadded.get_data()[..]);
+            // at most 256 guesses and one check per byte
+            assert!(found.queries <= cipher.len() * 257);
+        }
+    }
+
+    #[test]
+    fn check_padding_oracle_ambiguous() {
+        // the block decrypts to ..\x02\x02 w/ a zero iv, so the first guess that gives valid
+        // padding for the last byte is \x02 and only the check finds the real \x01
+        let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+        let oracle = PaddingOracle::new(Storage::new_init_vec(key.get_data(), "ascii"));
+        let plain = Storage::new_init_vec(&[2; BLOCK], "ascii");
+        let block = aes::ecb_encrypt(&plain, &key).get_data().clone();
+        let check = |iv: &[u8], cipher: &[u8]| oracle.padding_valid(iv, cipher);
+        assert_eq!(vec![2; BLOCK], decrypt_block(&check, &block));
+
+        let iv = vec![0; BLOCK];
+        let found = padding_oracle_decrypt(&check, &iv, &block);
+        assert_eq!(vec![2; BLOCK], found.plain);
+    }
+
+    #[test]
+    fn check_padding_oracle_encrypt() {
+        let oracle = PaddingOracle::random(&mut StdRng::seed_from_u64(20));
+        let check = |iv: &[u8], cipher: &[u8]| oracle.padding_valid(iv, cipher);
+        let forged = padding_oracle_encrypt(&check, b"forged w/o the key, block aligned", BLOCK);
+        assert_eq!(3 * BLOCK, forged.cipher.len());
+        assert_eq!(
+            Some(b"forged w/o the key, block aligned".to_vec()),
+            oracle.decrypt(&forged.iv, &forged.cipher)
+        );
+        assert!(forged.queries > 0);
+    }
+}
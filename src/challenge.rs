@@ -1,397 +1,831 @@
-use storage::Storage;
+use fixtures;
+use set1;
+use set2;
+use set3;
+use storage::{Encoding, Storage};
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+
+/// Challenge - a single cryptopals exercise (Send + Sync so run_all can run it on any worker)
+/// id (u32) - challenge number on cryptopals.com (numbered across every set)
+/// set (u32) - set the challenge belongs to
+/// title (&str) - short description shown in headers and reports
+/// run_with (Outcome) - solve the challenge (w/ inputs replaced by the overrides) and return the
+///                      expected and actual results
+pub trait Challenge: Send + Sync {
+    fn id(&self) -> u32;
+    fn set(&self) -> u32;
+    fn title(&self) -> &'static str;
+    fn run_with(&self, overrides: &Overrides) -> Outcome;
+
+    /// run -- solve the challenge w/ its canned inputs
+    fn run(&self) -> Outcome {
+        self.run_with(&Overrides::new())
+    }
+
+    /// header -- line printed above the outcome of the challenge
+    fn header(&self) -> String {
+        format!("Set {}, Challenge {} - {}", self.set(), self.id(), self.title())
+    }
+
+    /// verify -- run the challenge and compare its expected and actual results
+    fn verify(&self) -> Verdict {
+        self.run().verify()
+    }
+}
 
-/// Challenge - Struct to display each challenge
-pub struct Challenge {
-    header: String,
-    input: Option<String>,
-    input_file: Option<String>,
-    input_type: Option<String>,
-    input2: Option<String>,
-    input2_type: Option<String>,
-    key: Option<String>,
-    key_type: Option<String>,
-    expected_type: Option<String>,
-    actual_type: Option<String>,
-    expected_line: Option<i32>,
-    actual_line: Option<i32>,
-    expected_size: Option<usize>,
-    actual_size: Option<usize>,
-    expected_key: Option<String>,
-    actual_key: Option<String>,
-    expected_key_type: Option<String>,
-    actual_key_type: Option<String>,
-    expected_answer: Option<String>,
-    actual_answer: Option<String>,
-}
-
-trait ChallengeBuilder {
-    fn header(&mut self, header: String) -> &mut Self;
-    fn input(&mut self, input: Option<String>) -> &mut Self;
-    fn input_file(&mut self, input_file: Option<String>) -> &mut Self;
-    fn input_type(&mut self, input_type: Option<String>) -> &mut Self;
-    fn input2(&mut self, input2: Option<String>) -> &mut Self;
-    fn input2_type(&mut self, input2_type: Option<String>) -> &mut Self;
-    fn key(&mut self, key: Option<String>) -> &mut Self;
-    fn key_type(&mut self, key_type: Option<String>) -> &mut Self;
-    fn expected_type(&mut self, expected_type: Option<String>) -> &mut Self;
-    fn actual_type(&mut self, actual_type: Option<String>) -> &mut Self;
-    fn expected_line(&mut self, expected_line: Option<i32>) -> &mut Self;
-    fn actual_line(&mut self, actual_line: Option<i32>) -> &mut Self;
-    fn expected_size(&mut self, expected_size: Option<usize>) -> &mut Self;
-    fn actual_size(&mut self, actual_answer: Option<usize>) -> &mut Self;
-    fn expected_key(&mut self, expected_key: Option<String>) -> &mut Self;
-    fn actual_key(&mut self, actual_key: Option<String>) -> &mut Self;
-    fn expected_key_type(&mut self, expected_key_type: Option<String>) -> &mut Self;
-    fn actual_key_type(&mut self, actual_key_type: Option<String>) -> &mut Self;
-    fn expected_answer(&mut self, expected_answer: Option<String>) -> &mut Self;
-    fn actual_answer(&mut self, actual_answer: Option<String>) -> &mut Self;
-    fn build(&self) -> Challenge;
-}
-
-struct Builder {
-    header: String,
-    input: Option<String>,
-    input_file: Option<String>,
-    input_type: Option<String>,
-    input2: Option<String>,
-    input2_type: Option<String>,
-    key: Option<String>,
-    key_type: Option<String>,
-    expected_type: Option<String>,
-    actual_type: Option<String>,
-    expected_line: Option<i32>,
-    actual_line: Option<i32>,
-    expected_size: Option<usize>,
-    actual_size: Option<usize>,
-    expected_key: Option<String>,
-    actual_key: Option<String>,
-    expected_key_type: Option<String>,
-    actual_key_type: Option<String>,
-    expected_answer: Option<String>,
-    actual_answer: Option<String>,
-}
-
-impl Challenge {
-    fn print(&self) {
-        println!("{}", self.header);
-        match self.input {
-            Some(s) => println!("Input: {}", s),
-            None => ()
+/// registry -- every implemented challenge, ordered by id
+/// New challenges only have to be added to the challenges() list of their set.
+/// Parameters: void
+/// Return: Vec<Box<dyn Challenge>> - all challenges
+pub fn registry() -> Vec<Box<dyn Challenge>> {
+    let mut all = set1::challenges();
+    all.extend(set2::challenges());
+    all.extend(set3::challenges());
+    all.sort_by_key(|c| c.id());
+    all
+}
+
+/// find -- look up a challenge by set and id
+/// Parameters: set (u32) - set number
+///             id (u32) - challenge number
+/// Return: Option<Box<dyn Challenge>> - the challenge if it is implemented
+pub fn find(set: u32, id: u32) -> Option<Box<dyn Challenge>> {
+    registry().into_iter().find(|c| c.set() == set && c.id() == id)
+}
+
+/// select -- challenges that match every filter that is given
+/// Parameters: set (Option<u32>) - only keep challenges from this set
+///             ids (Option<(u32, u32)>) - only keep challenges w/ an id in this range (included)
+/// Return: Vec<Box<dyn Challenge>> - matching challenges ordered by id
+pub fn select(set: Option<u32>, ids: Option<(u32, u32)>) -> Vec<Box<dyn Challenge>> {
+    registry()
+        .into_iter()
+        .filter(|c| set.is_none_or(|s| c.set() == s))
+        .filter(|c| ids.is_none_or(|(lo, hi)| lo <= c.id() && c.id() <= hi))
+        .collect()
+}
+
+/// parse_range -- parse a challenge id ("3") or an inclusive range of ids ("3-7")
+/// Parameters: range (&str) - id or range
+/// Return: (u32, u32) - first and last id
+pub fn parse_range(range: &str) -> (u32, u32) {
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<u32>()
+            .expect("Please make sure challenge is a number or a range like 3-7!")
+    };
+    let (lo, hi) = match range.find('-') {
+        Some(i) => (parse(&range[..i]), parse(&range[i + 1..])),
+        None => (parse(range), parse(range)),
+    };
+    if lo > hi {
+        panic!("Error: the challenge range {} is empty", range);
+    }
+    (lo, hi)
+}
+
+/// Overrides - inputs given on the command line that replace the canned inputs of a challenge
+/// The expected results only hold for the canned inputs, so they are dropped when anything is
+/// overridden.
+/// input (Option<String>) - replaces the inline input (or the input file)
+/// input_type (Option<String>) - replaces the encoding of the input (ascii, hex or base64)
+/// input_file (Option<String>) - replaces the input file ("-" reads stdin).  Challenges w/ an
+///                               inline input read the file into the input instead.
+/// key (Option<String>) - replaces the key
+#[derive(Clone, Default)]
+pub struct Overrides {
+    pub input: Option<String>,
+    pub input_type: Option<String>,
+    pub input_file: Option<String>,
+    pub key: Option<String>,
+}
+
+impl Overrides {
+    pub fn new() -> Overrides {
+        Overrides::default()
+    }
+
+    /// is_empty -- true when nothing is overridden
+    pub fn is_empty(&self) -> bool {
+        self.input.is_none() && self.input_type.is_none() && self.input_file.is_none() && self.key.is_none()
+    }
+}
+
+/// read_source -- read a whole file, "-" reads stdin
+/// Parameters: filename (&str) - file to read or "-"
+/// Return: String - contents
+pub fn read_source(filename: &str) -> String {
+    if filename == "-" {
+        let mut contents = String::new();
+        io::stdin()
+            .read_to_string(&mut contents)
+            .expect("Error: Unable to read stdin");
+        return contents;
+    }
+    fs::read_to_string(filename).expect("Error: Unable to read file")
+}
+
+/// Input - where the main input of a challenge comes from
+/// Inline (Storage) - data given in the challenge
+/// File (String, Encoding) - file w/ data in the encoding ("-" reads stdin)
+/// Fixture (&str, Encoding) - bundled input file (see fixtures) w/ data in the encoding
+pub enum Input {
+    Inline(Storage),
+    File(String, Encoding),
+    Fixture(&'static str, Encoding),
+}
+
+impl Input {
+    /// encoding -- encoding of the inline data or the file
+    pub fn encoding(&self) -> Encoding {
+        match *self {
+            Input::Inline(ref s) => Encoding::parse(s.get_data_type()),
+            Input::File(_, e) | Input::Fixture(_, e) => e,
         }
-        match self.input_file {
-            Some(s) => println!("Input File: {}", s),
-            None => ()
+    }
+}
+
+/// Value - an expected or actual result
+/// Text (String) - answer, key or anything else written as text
+/// Number (u64) - line number, key size or any other count
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Text(String),
+    Number(u64),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Text(ref t) => write!(f, "{}", t),
+            Value::Number(n) => write!(f, "{}", n),
         }
-        match self.input_type {
-            Some(s) => println!("Input Type: {}", s),
-            None => ()
+    }
+}
+
+impl<'a> From<&'a str> for Value {
+    fn from(t: &'a str) -> Value {
+        Value::Text(t.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(t: String) -> Value {
+        Value::Text(t)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Value {
+        Value::Number(n as u64)
+    }
+}
+
+/// Field - a named result w/ its expected and actual value
+/// name (&str) - name of the result (answer, key, line, size, nonce...)
+/// expected (Option<Value>) - expected value, None when it isn't checked
+/// actual (Option<Value>) - actual value, None until the challenge sets it
+pub struct Field {
+    pub name: &'static str,
+    pub expected: Option<Value>,
+    pub actual: Option<Value>,
+}
+
+/// Outcome - Struct to display the inputs, expected and actual results of each challenge
+pub struct Outcome {
+    header: String,
+    input: Input,
+    input2: Option<Storage>,
+    key: Option<Storage>,
+    output: Encoding,
+    results: Vec<Field>,
+}
+
+/// FieldDiff - an expected field that does not match its actual counterpart
+/// field (&str) - name of the expected field
+/// expected (String) - expected value
+/// actual (Option<String>) - actual value (None when the challenge never set it)
+pub struct FieldDiff {
+    pub field: &'static str,
+    pub expected: String,
+    pub actual: Option<String>,
+}
+
+impl FieldDiff {
+    /// first_difference -- index of the first character that differs
+    /// Parameters: void
+    /// Return: Option<usize> - None when the actual value is missing
+    pub fn first_difference(&self) -> Option<usize> {
+        self.actual.as_ref().map(|a| {
+            self.expected
+                .chars()
+                .zip(a.chars())
+                .take_while(|(e, a)| e == a)
+                .count()
+        })
+    }
+
+    pub fn print(&self) {
+        match (self.actual.as_ref(), self.first_difference()) {
+            (Some(a), Some(i)) => println!(
+                "  {}: expected {:?}, actual {:?} (first difference at character {})",
+                self.field, self.expected, a, i
+            ),
+            _ => println!("  {}: expected {:?}, actual value is missing", self.field, self.expected),
         }
-        match self.input2 {
-            Some(s) => println!("Input2: {}", s),
-            None => ()
+    }
+}
+
+/// Verdict - result of comparing every expected field of an outcome w/ its actual counterpart
+/// checked (Vec<&str>) - fields that had an expected value
+/// diffs (Vec<FieldDiff>) - fields that did not match
+pub struct Verdict {
+    pub checked: Vec<&'static str>,
+    pub diffs: Vec<FieldDiff>,
+}
+
+impl Verdict {
+    /// passed -- true when every checked field matched (nothing checked also passes)
+    pub fn passed(&self) -> bool {
+        self.diffs.is_empty()
+    }
+
+    pub fn print(&self) {
+        if self.checked.is_empty() {
+            println!("Result: PASS (nothing to verify)");
+        } else if self.passed() {
+            println!("Result: PASS ({})", self.checked.join(", "));
+        } else {
+            println!("Result: FAIL ({} of {} fields differ)", self.diffs.len(), self.checked.len());
+            for d in &self.diffs {
+                d.print();
+            }
         }
-        match self.input2_type {
-            Some(s) => println!("Input2 Type: {}", s),
-            None => ()
+    }
+}
+
+/// copy -- helper function to copy a storage (Storage isn't Clone)
+fn copy(s: &Storage) -> Storage {
+    Storage::new_init_vec(s.get_data(), s.get_data_type())
+}
+
+impl Outcome {
+    pub fn print(&self) {
+        println!("{}", self.header);
+        match self.input {
+            Input::Inline(ref s) => println!("Input: {}", s),
+            Input::File(ref f, _) => println!("Input File: {}", f),
+            Input::Fixture(f, _) => println!("Input Fixture: {}", f),
         }
-        match self.key {
-            Some(s) => println!("Key: {}", s),
-            None => ()
+        println!("Input Type: {}", self.input.encoding().as_str());
+        if let Some(s) = self.input2.as_ref() {
+            println!("Input2: {}", s);
+            println!("Input2 Type: {}", s.get_data_type());
         }
-        match self.key_type {
-            Some(s) => println!("Key Type: {}", s),
-            None => ()
-        } 
-        match self.expected_key {
-            Some(s) => println!("Expected Key: {}", s),
-            None => ()
+        if let Some(s) = self.key.as_ref() {
+            println!("Key: {}", s);
+            println!("Key Type: {}", s.get_data_type());
         }
-        match self.actual_key {
-            Some(s) => println!("Actual Key: {}", s),
-            None => ()
+        println!("Output Type: {}", self.output.as_str());
+        for f in &self.results {
+            let name = capitalize(f.name);
+            if let Some(v) = f.expected.as_ref() {
+                println!("Expected {}: {}", name, v);
+            }
+            if let Some(v) = f.actual.as_ref() {
+                println!("Actual {}: {}", name, v);
+            }
         }
-        match self.expected_key_type {
-            Some(s) => println!("Expected Key Type: {}", s),
-            None => ()
+    }
+
+    /// verify -- compare every expected field w/ its actual counterpart
+    /// Parameters: void
+    /// Return: Verdict - checked fields and the ones that did not match
+    pub fn verify(&self) -> Verdict {
+        let mut verdict = Verdict {
+            checked: Vec::new(),
+            diffs: Vec::new(),
+        };
+        for f in &self.results {
+            if let Some(expected) = f.expected.as_ref() {
+                verdict.checked.push(f.name);
+                if f.actual.as_ref() != Some(expected) {
+                    verdict.diffs.push(FieldDiff {
+                        field: f.name,
+                        expected: expected.to_string(),
+                        actual: f.actual.as_ref().map(|a| a.to_string()),
+                    });
+                }
+            }
         }
-        match self.actual_key_type {
-            Some(s) => println!("Actual Key Type: {}", s),
-            None => ()
+        verdict
+    }
+
+    /// fields -- every field that is set, in the order print shows them
+    /// Parameters: void
+    /// Return: Vec<(String, String)> - (field name, value)
+    pub fn fields(&self) -> Vec<(String, String)> {
+        let mut out: Vec<(String, String)> = Vec::new();
+        match self.input {
+            Input::Inline(ref s) => out.push(("input".to_owned(), s.to_string())),
+            Input::File(ref f, _) => out.push(("input_file".to_owned(), f.clone())),
+            Input::Fixture(f, _) => out.push(("input_fixture".to_owned(), f.to_owned())),
         }
-        match self.expected_type {
-            Some(s) => println!("Expected Type: {}", s),
-            None => ()
+        out.push(("input_type".to_owned(), self.input.encoding().as_str().to_owned()));
+        if let Some(s) = self.input2.as_ref() {
+            out.push(("input2".to_owned(), s.to_string()));
+            out.push(("input2_type".to_owned(), s.get_data_type().clone()));
         }
-        match self.actual_type {
-            Some(s) => println!("Actual Type: {}", s),
-            None => ()
+        if let Some(s) = self.key.as_ref() {
+            out.push(("key".to_owned(), s.to_string()));
+            out.push(("key_type".to_owned(), s.get_data_type().clone()));
         }
-        match self.expected_line {
-            Some(s) => println!("Expected Line Number: {}", s),
-            None => ()
+        out.push(("output_type".to_owned(), self.output.as_str().to_owned()));
+        for f in &self.results {
+            out.extend(f.expected.as_ref().map(|v| (format!("expected_{}", f.name), v.to_string())));
+            out.extend(f.actual.as_ref().map(|v| (format!("actual_{}", f.name), v.to_string())));
         }
-        match self.actual_line {
-            Some(s) => println!("Actual Line Number: {}", s),
-            None => ()
+        out
+    }
+
+    /// summary -- short description of the actual results for report tables
+    /// Parameters: void
+    /// Return: String - every actual result but the answer, or the start of the answer
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        for f in self.results.iter().filter(|f| f.name != "answer") {
+            match f.actual.as_ref() {
+                Some(Value::Text(t)) => parts.push(format!("{}={:?}", f.name, t)),
+                Some(&Value::Number(n)) => parts.push(format!("{}={}", f.name, n)),
+                None => (),
+            }
         }
-        match self.expected_size {
-            Some(s) => println!("Expected Size: {}", s),
-            None => ()
+        if parts.is_empty() {
+            if let Some(a) = self.get_actual("answer") {
+                let a = a.to_string();
+                let start: String = a.chars().take(32).collect();
+                let dots = if a.chars().count() > 32 { "..." } else { "" };
+                parts.push(format!("{:?}{}", start, dots));
+            }
         }
-        match self.actual_size {
-            Some(s) => println!("Actual Size: {}", s),
-            None => ()
+        parts.join(" ")
+    }
+
+    /// apply -- replace the canned inputs w/ the overrides and drop the expected results
+    /// The output encoding is how the answer is written, so it is kept.
+    fn apply(&mut self, overrides: &Overrides) {
+        if overrides.is_empty() {
+            return;
         }
-        match self.expected_answer {
-            Some(s) => println!("Expected Answer: {}", s),
-            None => ()
+        let encoding = overrides
+            .input_type
+            .as_ref()
+            .map_or(self.input.encoding(), |t| Encoding::parse(t));
+        let input = if let Some(i) = overrides.input.as_ref() {
+            Input::Inline(Storage::new_init(i, encoding.as_str()))
+        } else if let Some(f) = overrides.input_file.as_ref() {
+            match self.input {
+                Input::Inline(_) => Input::Inline(parse_text(read_source(f).trim_end(), encoding)),
+                Input::File(..) | Input::Fixture(..) => Input::File(f.clone(), encoding),
+            }
+        } else {
+            match self.input {
+                Input::Inline(ref s) => Input::Inline(Storage::new_init(&s.to_string(), encoding.as_str())),
+                Input::File(ref f, _) => Input::File(f.clone(), encoding),
+                Input::Fixture(f, _) => Input::Fixture(f, encoding),
+            }
+        };
+        self.input = input;
+        if let Some(k) = overrides.key.as_ref() {
+            let key_type = self.key.as_ref().map_or("ascii".to_owned(), |s| s.get_data_type().clone());
+            self.key = Some(Storage::new_init(k, &key_type));
         }
-        match self.actual_answer {
-            Some(s) => println!("Actual Answer: {}", s),
-            None => ()
+
+        for f in self.results.iter_mut() {
+            f.expected = None;
         }
     }
 
     // Setters
-    fn set_actual_type(&mut self, actual_type: &str) {
-        self.actual_type = Some(actual_type.to_owned());
-    }
-
-    fn set_actual_line(&mut self, actual_line: i32) {
-        self.actual_line = Some(actual_line);
+    /// set_actual -- record an actual result (fields w/o an expected value are added)
+    /// Parameters: name (&str) - name of the result
+    ///             value (Into<Value>) - actual value
+    /// Return: void
+    pub fn set_actual<V: Into<Value>>(&mut self, name: &'static str, value: V) {
+        let value = value.into();
+        match self.results.iter_mut().find(|f| f.name == name) {
+            Some(f) => f.actual = Some(value),
+            None => self.results.push(Field {
+                name,
+                expected: None,
+                actual: Some(value),
+            }),
+        }
     }
 
-    fn set_actual_size(&mut self, actual_size: usize) {
-        self.actual_size = Some(actual_size);
+    // Getters
+    pub fn get_header(&self) -> &str {
+        &self.header
     }
 
-    fn set_actual_key(&mut self, actual_key: &str) {
-        self.actual_key = Some(actual_key.to_owned());
+    /// get_input -- the main input in its encoding.  Files are read here ("-" reads stdin),
+    /// ascii files are raw bytes and whitespace is ignored in hex and base64 files.
+    /// Parameters: void
+    /// Return: Storage - input
+    pub fn get_input(&self) -> Storage {
+        match self.input {
+            Input::Inline(ref s) => copy(s),
+            Input::File(ref f, Encoding::Ascii) if f != "-" => {
+                Storage::new_init_vec(&fs::read(f).expect("Error: Unable to read file"), "ascii")
+            }
+            Input::File(ref f, e) => parse_text(&read_source(f), e),
+            Input::Fixture(f, e) => parse_text(&fixtures::read(f), e),
+        }
     }
 
-    fn set_actual_answer(&mut self, actual_answer: &str) {
-        self.actual_answer = Some(actual_answer.to_owned());
+    /// get_input_lines -- the main input split into lines (empty lines are kept so line
+    /// numbers match the file)
+    /// Parameters: void
+    /// Return: Vec<Storage> - one storage per line in the input encoding
+    pub fn get_input_lines(&self) -> Vec<Storage> {
+        let e = self.input.encoding();
+        let contents = match self.input {
+            Input::Inline(ref s) if e != Encoding::Ascii => s.to_string(),
+            Input::Inline(ref s) => s.get_data().iter().map(|b| *b as char).collect(),
+            Input::File(ref f, _) => read_source(f),
+            Input::Fixture(f, _) => fixtures::read(f),
+        };
+        contents
+            .lines()
+            .map(|l| Storage::new_init(if e == Encoding::Ascii { l } else { l.trim() }, e.as_str()))
+            .collect()
     }
 
-    // Getters
-    fn get_input(&self) -> &str {
-        &self.input.unwrap()
+    pub fn get_input2(&self) -> Option<&Storage> {
+        self.input2.as_ref()
     }
 
-    fn get_input_file(&self) -> &str {
-        &self.input_file.unwrap()
+    pub fn get_key(&self) -> Option<&Storage> {
+        self.key.as_ref()
     }
 
-    fn get_input_type(&self) -> &str {
-        &self.input_type.unwrap()
+    pub fn get_output(&self) -> Encoding {
+        self.output
     }
 
-    fn get_input2(&self) -> &str {
-        &self.input2.unwrap()
+    pub fn get_expected(&self, name: &str) -> Option<&Value> {
+        self.results.iter().find(|f| f.name == name).and_then(|f| f.expected.as_ref())
     }
 
-    fn get_input2_type(&self) -> &str {
-        &self.input2_type.unwrap()
+    pub fn get_actual(&self, name: &str) -> Option<&Value> {
+        self.results.iter().find(|f| f.name == name).and_then(|f| f.actual.as_ref())
     }
+}
 
-    fn get_key(&self) -> &str {
-        &self.key.unwrap()
+/// parse_text -- text in an encoding to a storage (whitespace is ignored in hex and base64)
+fn parse_text(contents: &str, encoding: Encoding) -> Storage {
+    match encoding {
+        Encoding::Ascii => Storage::new_init(contents, "ascii"),
+        e => {
+            let contents: String = contents.chars().filter(|c| !c.is_whitespace()).collect();
+            Storage::new_init(&contents, e.as_str())
+        }
     }
+}
 
-    fn get_key_type(&self) -> &str {
-        &self.key_type.unwrap()
+/// capitalize -- first letter in upper case (field names in print)
+fn capitalize(name: &str) -> String {
+    let mut c = name.chars();
+    match c.next() {
+        Some(first) => first.to_uppercase().chain(c).collect(),
+        None => String::new(),
     }
+}
 
-    fn get_expected_type(&self) -> &str {
-        &self.expected_type.unwrap()
-    }
+/// Missing - a required builder field that has not been set yet
+pub struct Missing;
+
+/// Builder - builds the Outcome of a challenge
+/// The header and the input are required: build() only exists once both are set, so a
+/// challenge that forgets one doesn't compile.  Everything else is optional.
+/// header (H) - Missing, then String
+/// input (I) - Missing, then Input
+pub struct Builder<H, I> {
+    header: H,
+    input: I,
+    input2: Option<Storage>,
+    key: Option<Storage>,
+    output: Encoding,
+    results: Vec<Field>,
+    overrides: Overrides,
+}
 
-    fn get_expected_key_type(&self) -> &str {
-        &self.expected_key_type.unwrap()
+impl Default for Builder<Missing, Missing> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Builder {
-    fn new() -> Builder {
+impl Builder<Missing, Missing> {
+    pub fn new() -> Builder<Missing, Missing> {
         Builder {
-            header: "",
-            input: None,
-            input_file: None,
-            input_type: None,
+            header: Missing,
+            input: Missing,
             input2: None,
-            input2_type: None,
             key: None,
-            key_type: None,
-            expected_type: None,
-            actual_type: None,
-            expected_line: None,
-            actual_line: None,
-            expected_size: None,
-            actual_size: None,
-            expected_key: None,
-            actual_key: None,
-            expected_key_type: None,
-            actual_key_type: None,
-            expected_answer: None,
-            actual_answer: None
+            output: Encoding::Ascii,
+            results: Vec::new(),
+            overrides: Overrides::new(),
         }
     }
+}
 
-    fn set_header(&mut self, header: &str) -> &mut Builder {
-        self.header = header.to_owned();
-        self
-    }
-
-    fn set_input(&mut self, input: &str) -> &mut Builder {
-        self.input = Some(input.to_owned());
-        self
-    }
-    
-    fn set_input_file(&mut self, input_file: &str) -> &mut Builder {
-        self.input_file = Some(input_file.to_owned());
-        self
-    }
-
-    fn set_input_type(&mut self, input_type: &str) -> &mut Builder {
-        self.input_type = Some(input_type.to_owned());
-        self
+impl<I> Builder<Missing, I> {
+    pub fn header(self, header: &str) -> Builder<String, I> {
+        Builder {
+            header: header.to_owned(),
+            input: self.input,
+            input2: self.input2,
+            key: self.key,
+            output: self.output,
+            results: self.results,
+            overrides: self.overrides,
+        }
     }
+}
 
-    fn set_input2(&mut self, input2: &str) -> &mut Builder {
-        self.input2 = Some(input2.to_owned());
-        self
+impl<H> Builder<H, Missing> {
+    /// input -- inline input (any encoding)
+    pub fn input(self, input: Storage) -> Builder<H, Input> {
+        self.source(Input::Inline(input))
     }
 
-    fn set_input2_type(&mut self, input2_type: &str) -> &mut Builder {
-        self.input2_type = Some(input2_type.to_owned());
-        self
+    /// input_file -- input read from a file when the challenge runs
+    pub fn input_file(self, input_file: &str, encoding: Encoding) -> Builder<H, Input> {
+        self.source(Input::File(input_file.to_owned(), encoding))
     }
 
-    fn set_key(&mut self, key: &str) -> &mut Builder {
-        self.key = Some(key.to_owned());
-        self
+    /// input_fixture -- input read from a bundled input file when the challenge runs
+    pub fn input_fixture(self, name: &'static str, encoding: Encoding) -> Builder<H, Input> {
+        self.source(Input::Fixture(name, encoding))
     }
 
-    fn set_key_type(&mut self, key_type: &str) -> &mut Builder {
-        self.key_type = Some(key_type.to_owned());
-        self
+    fn source(self, input: Input) -> Builder<H, Input> {
+        Builder {
+            header: self.header,
+            input,
+            input2: self.input2,
+            key: self.key,
+            output: self.output,
+            results: self.results,
+            overrides: self.overrides,
+        }
     }
+}
 
-    fn set_expected_type(&mut self, expected_type: &str) -> &mut Builder {
-        self.expected_type = Some(expected_type.to_owned());
+impl<H, I> Builder<H, I> {
+    pub fn input2(mut self, input2: Storage) -> Builder<H, I> {
+        self.input2 = Some(input2);
         self
     }
 
-    fn set_expected_line(&mut self, expected_line: i32) -> &mut Builder {
-        self.expected_line = Some(expected_line);
+    pub fn key(mut self, key: Storage) -> Builder<H, I> {
+        self.key = Some(key);
         self
     }
 
-    fn set_expected_size(&mut self, expected_size: usize) -> &mut Builder {
-        self.expected_size = Some(expected_size);
-        self
-    }
-    
-    fn set_expected_key(&mut self, expected_key: &str) -> &mut Builder {
-        self.expected_key = Some(expected_key.to_owned());
+    /// output -- encoding the answer is written in (ascii by default)
+    pub fn output(mut self, output: Encoding) -> Builder<H, I> {
+        self.output = output;
         self
     }
 
-    fn set_expected_key_type(&mut self, expected_key_type: &str) -> &mut Builder {
-        self.expected_key_type = Some(expected_key_type.to_owned());
+    /// expect -- expected value of a named result (checked in the order they are added)
+    pub fn expect<V: Into<Value>>(mut self, name: &'static str, value: V) -> Builder<H, I> {
+        self.results.push(Field {
+            name,
+            expected: Some(value.into()),
+            actual: None,
+        });
         self
     }
 
-    fn set_expected_answer(&mut self, expected_answer: &str) -> &mut Builder {
-        self.expected_answer = Some(expected_answer.to_owned());
+    pub fn overrides(mut self, overrides: &Overrides) -> Builder<H, I> {
+        self.overrides = overrides.clone();
         self
     }
+}
 
-    fn build_challenge(&self) -> Challenge {
-        Challenge {
+impl Builder<String, Input> {
+    pub fn build(self) -> Outcome {
+        let mut outcome = Outcome {
             header: self.header,
             input: self.input,
-            input_file: self.input_file,
-            input_type: self.input_type,
             input2: self.input2,
-            input2_type: self.input2_type,
             key: self.key,
-            key_type: self.key_type,
-            expected_type: self.expected_type,
-            actual_type: self.actual_type,
-            expected_line: self.expected_line,
-            actual_line: self.actual_line,
-            expected_size: self.expected_size,
-            actual_size: self.actual_size,
-            expected_key: self.expected_key,
-            actual_key: self.actual_key,
-            expected_key_type: self.expected_key_type,
-            actual_key_type: self.actual_key_type,
-            expected_answer: self.expected_answer,
-            actual_answer: self.actual_answer
-        }
+            output: self.output,
+            results: self.results,
+        };
+        outcome.apply(&self.overrides);
+        outcome
     }
 }
 
-impl ChallengeBuilder for Builder {
-    fn header(&mut self, header: &str) -> &mut Self {
-        self.set_header(header)
-    }
-
-    fn input(&mut self, input: &str) -> &mut Self {
-        self.set_input(input)
-    }
-
-    fn input_file(&mut self, input_file: &str) -> &mut Self {
-        self.set_input_file(input_file)
-    }
-
-    fn input_type(&mut self, input_type: &str) -> &mut Self {
-        self.set_input_type(input_type)
-    }
-
-    fn input2(&mut self, input2: &str) -> &mut Self {
-        self.set_input2(input2)
-    }
-
-    fn input2_type(&mut self, input2_type: &str) -> &mut Self {
-        self.set_input2_type(input2_type)
-    }
-
-    fn key(&mut self, key: &str) -> &mut Self {
-        self.set_key(key)
-    }
-
-    fn key_type(&mut self, key_type: &str) -> &mut Self {
-        self.set_key_type(key_type)
-    }
-    
-    fn expected_type(&mut self, expected_type: &str) -> &mut Self {
-        self.set_expected_type(expected_type)
-    }
-
-    fn expected_line(&mut self, expected_line: i32) -> &mut Self {
-        self.set_expected_line(expected_line)
-    }
-
-    fn expected_size(&mut self, expected_size: usize) -> &mut Self {
-        self.set_expected_size(expected_size)
-    }
-
-    fn expected_key(&mut self, expected_key: &str) -> &mut Self {
-        self.set_expected_key(expected_key)
-    }
-
-    fn expected_key_type(&mut self, expected_key_type: &str) -> &mut Self {
-        self.set_expected_key_type(expected_key_type)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // TEST registry, find
+    #[test]
+    fn check_registry() {
+        let all = registry();
+        assert!(all.len() >= 7);
+        assert!(all.windows(2).all(|c| c[0].id() < c[1].id()));
+        assert!(all.iter().all(|c| !c.title().is_empty()));
+    }
+
+    #[test]
+    fn check_registry_verifies() {
+        for c in registry().iter() {
+            let verdict = c.verify();
+            assert!(verdict.passed(), "{} failed", c.header());
+            assert!(!verdict.checked.is_empty());
+        }
     }
 
-    fn expected_answer(&mut self, expected_answer: &str) -> &mut Self {
-        self.set_expected_answer(expected_answer)
-    }
-     
-    fn build(&self) -> Challenge {
-        self.build_challenge()
+    #[test]
+    fn check_find() {
+        let c = find(1, 3).unwrap();
+        assert_eq!(3, c.id());
+        assert_eq!(1, c.set());
+        assert_eq!("Set 1, Challenge 3 - Single-byte XOR cipher", c.header());
+        assert!(find(1, 99).is_none());
+        assert!(find(2, 3).is_none());
+    }
+
+    // TEST select, parse_range
+    #[test]
+    fn check_select() {
+        let ids = |v: Vec<Box<dyn Challenge>>| v.iter().map(|c| c.id()).collect::<Vec<u32>>();
+        assert_eq!(vec![3, 4, 5], ids(select(None, Some((3, 5)))));
+        assert_eq!(vec![2], ids(select(Some(1), Some((2, 2)))));
+        assert!(select(Some(1), None).len() >= 7);
+        assert!(select(Some(99), None).is_empty());
+    }
+
+    #[test]
+    fn check_parse_range() {
+        assert_eq!((3, 7), parse_range("3-7"));
+        assert_eq!((4, 4), parse_range("4"));
+        assert_eq!((1, 2), parse_range(" 1 - 2 "));
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_parse_range() {
+        parse_range("7-3");
+    }
+
+    // TEST Builder, Outcome
+    #[test]
+    fn check_builder() {
+        let mut outcome = Builder::new()
+            .header("header")
+            .input(Storage::new_init("input", "ascii"))
+            .expect("answer", "answer")
+            .build();
+        assert_eq!("", outcome.summary());
+        outcome.set_actual("answer", "answer");
+        assert_eq!("\"answer\"", outcome.summary());
+        outcome.set_actual("line", 4);
+        assert_eq!("line=4", outcome.summary());
+        assert_eq!("header", outcome.get_header());
+        assert_eq!("input", outcome.get_input().to_string());
+        assert_eq!(Encoding::Ascii, outcome.get_output());
+        let fields = |o: &Outcome| -> Vec<(String, String)> { o.fields() };
+        assert_eq!(
+            vec![
+                ("input", "input"),
+                ("input_type", "ascii"),
+                ("output_type", "ascii"),
+                ("expected_answer", "answer"),
+                ("actual_answer", "answer"),
+                ("actual_line", "4"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect::<Vec<(String, String)>>(),
+            fields(&outcome)
+        );
+        assert_eq!(Some(&Value::Number(4)), outcome.get_actual("line"));
+        assert_eq!(Some(&Value::Text("answer".to_owned())), outcome.get_expected("answer"));
+    }
+
+    #[test]
+    fn check_builder_named_results() {
+        let mut outcome = Builder::new()
+            .header("header")
+            .input_file("./input_files/set1challenge4.txt", Encoding::Hex)
+            .key(Storage::new_init("YELLOW SUBMARINE", "ascii"))
+            .expect("key", "K")
+            .expect("plaintext", "hello")
+            .expect("nonce", 0)
+            .build();
+        assert_eq!(327, outcome.get_input_lines().len());
+        outcome.set_actual("key", "K");
+        outcome.set_actual("plaintext", "hello");
+        outcome.set_actual("nonce", 0);
+        let verdict = outcome.verify();
+        assert_eq!(vec!["key", "plaintext", "nonce"], verdict.checked);
+        assert!(verdict.passed());
+        assert_eq!("key=\"K\" plaintext=\"hello\" nonce=0", outcome.summary());
+    }
+
+    // TEST Overrides
+    #[test]
+    fn check_overrides() {
+        let mut overrides = Overrides::new();
+        assert!(overrides.is_empty());
+        overrides.input = Some("1b37".to_owned());
+        overrides.key = Some("K".to_owned());
+
+        let outcome = Builder::new()
+            .header("header")
+            .overrides(&overrides)
+            .input_file("./input_files/set1challenge4.txt", Encoding::Hex)
+            .key(Storage::new_init("ICE", "ascii"))
+            .output(Encoding::Base64)
+            .expect("answer", "answer")
+            .build();
+        assert_eq!("1b37", outcome.get_input().to_string());
+        assert_eq!("hex", outcome.get_input().get_data_type());
+        assert_eq!("K", outcome.get_key().unwrap().to_string());
+        assert_eq!(Encoding::Base64, outcome.get_output());
+        assert!(outcome.verify().checked.is_empty());
+    }
+
+    #[test]
+    fn check_overrides_input_file() {
+        let mut overrides = Overrides::new();
+        overrides.input_file = Some("./input_files/set1challenge4.txt".to_owned());
+
+        let inline = Builder::new()
+            .header("header")
+            .overrides(&overrides)
+            .input(Storage::new_init("1b37", "hex"))
+            .build();
+        let input = inline.get_input().to_string();
+        assert!(input.starts_with("0e3647e8592d35514a081243582536ed3de6734059001e3f535ce6271032"));
+
+        let file = Builder::new()
+            .header("header")
+            .overrides(&overrides)
+            .input_file("other.txt", Encoding::Hex)
+            .build();
+        assert!(file.fields().contains(&("input_file".to_owned(), "./input_files/set1challenge4.txt".to_owned())));
+    }
+
+    #[test]
+    fn check_registry_overrides() {
+        let mut overrides = Overrides::new();
+        overrides.input = Some("2935387d2c28343e367d3f2f322a337d3b3225".to_owned());
+        let outcome = find(1, 3).unwrap().run_with(&overrides);
+        assert!(outcome.verify().checked.is_empty());
+        assert!(outcome.fields().contains(&("actual_key".to_owned(), "]".to_owned())));
+        assert!(outcome.fields().contains(&("actual_answer".to_owned(), "the quick brown fox".to_owned())));
+    }
+
+    // TEST verify
+    #[test]
+    fn check_verify() {
+        let mut outcome = Builder::new()
+            .header("header")
+            .input(Storage::new_init("", "ascii"))
+            .expect("answer", "hello world")
+            .expect("key", "K")
+            .expect("line", 3)
+            .build();
+        outcome.set_actual("answer", "hello there");
+        outcome.set_actual("line", 3);
+
+        let verdict = outcome.verify();
+        assert!(!verdict.passed());
+        assert_eq!(vec!["answer", "key", "line"], verdict.checked);
+        assert_eq!(2, verdict.diffs.len());
+        assert_eq!("answer", verdict.diffs[0].field);
+        assert_eq!(Some(6), verdict.diffs[0].first_difference());
+        assert_eq!("key", verdict.diffs[1].field);
+        assert_eq!(None, verdict.diffs[1].first_difference());
+
+        outcome.set_actual("answer", "hello world");
+        outcome.set_actual("key", "K");
+        assert!(outcome.verify().passed());
+
+        // a number never matches the same digits as text
+        outcome.set_actual("line", "3");
+        assert!(!outcome.verify().passed());
     }
 }
-
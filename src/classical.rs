@@ -0,0 +1,474 @@
+//! classical (alphabetic) ciphers and their breakers
+//! only the letters A-Z / a-z are enciphered, case and every other character is kept
+
+use analysis;
+use set1::helper::freq;
+use storage::Storage;
+use xor;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// ETAOIN -- english letters from most to least common
+const ETAOIN: &[u8; 26] = b"ETAOINSHRDLCUMWFGYPBVKJXQZ";
+
+/// map_letters -- helper function to replace every letter w/ f(index of letter, letter number)
+/// Parameters: text (&str) - input text
+///             f (FnMut(usize, u8) -> u8) - letter number (A = 0) -> new letter number,
+///                                          the first parameter only counts letters
+/// Return: String - text w/ the letters replaced
+fn map_letters<F: FnMut(usize, u8) -> u8>(text: &str, mut f: F) -> String {
+    let mut count = 0;
+    text.chars()
+        .map(|c| {
+            if !c.is_ascii_alphabetic() {
+                return c;
+            }
+            let base = if c.is_ascii_uppercase() { b'A' } else { b'a' };
+            let out = f(count, c as u8 - base) % 26;
+            count += 1;
+            (base + out) as char
+        })
+        .collect()
+}
+
+/// letters -- helper function to get the letter numbers (A = 0) of the letters in text
+/// Parameters: text (&str) - input text
+/// Return: Vec<u8> - letter numbers
+fn letters(text: &str) -> Vec<u8> {
+    text.bytes()
+        .filter(|b| b.is_ascii_alphabetic())
+        .map(|b| b.to_ascii_uppercase() - b'A')
+        .collect()
+}
+
+/// parse_key -- helper function to turn an alphabetic key into letter numbers
+/// Parameters: key (&str) - key (letters only)
+/// Return: Vec<u8> - letter numbers
+fn parse_key(key: &str) -> Vec<u8> {
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphabetic()) {
+        panic!("Error: key must be one or more letters ({})", key);
+    }
+    letters(key)
+}
+
+/// parse_alphabet -- helper function to check a substitution alphabet
+/// Parameters: key (&str) - 26 letters, each letter exactly once
+/// Return: Vec<u8> - letter numbers
+fn parse_alphabet(key: &str) -> Vec<u8> {
+    let k = parse_key(key);
+    let mut seen = [false; 26];
+    for l in &k {
+        seen[*l as usize] = true;
+    }
+    if k.len() != 26 || !seen.iter().all(|s| *s) {
+        panic!("Error: substitution key must contain every letter once ({})", key);
+    }
+    k
+}
+
+/// gcd -- greatest common divisor
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// mod_inverse_26 -- multiplicative inverse of a mod 26
+/// Parameters: a (u8) - number coprime w/ 26
+/// Return: u8 - inverse of a
+fn mod_inverse_26(a: u8) -> u8 {
+    if gcd(a as u32, 26) != 1 {
+        panic!("Error: {} has no inverse mod 26", a);
+    }
+    (1u8..26u8).find(|i| (a as u32 * *i as u32) % 26 == 1).unwrap()
+}
+
+/// caesar_encrypt -- shift every letter forward
+/// Parameters: plaintext (&str) - message
+///             shift (u8) - number of letters to shift (3 is Caesar's choice)
+/// Return: String - ciphertext
+pub fn caesar_encrypt(plaintext: &str, shift: u8) -> String {
+    map_letters(plaintext, |_, l| l + shift % 26)
+}
+
+/// caesar_decrypt -- shift every letter backward
+/// Parameters: ciphertext (&str) - encrypted message
+///             shift (u8) - number of letters that were shifted
+/// Return: String - plaintext
+pub fn caesar_decrypt(ciphertext: &str, shift: u8) -> String {
+    map_letters(ciphertext, |_, l| l + 26 - shift % 26)
+}
+
+/// vigenere_encrypt -- caesar shift each letter by the next letter of the key
+/// the key only advances on letters
+/// Parameters: plaintext (&str) - message
+///             key (&str) - key (letters only)
+/// Return: String - ciphertext
+pub fn vigenere_encrypt(plaintext: &str, key: &str) -> String {
+    let k = parse_key(key);
+    map_letters(plaintext, |i, l| l + k[i % k.len()])
+}
+
+/// vigenere_decrypt -- reverse of vigenere_encrypt
+/// Parameters: ciphertext (&str) - encrypted message
+///             key (&str) - key (letters only)
+/// Return: String - plaintext
+pub fn vigenere_decrypt(ciphertext: &str, key: &str) -> String {
+    let k = parse_key(key);
+    map_letters(ciphertext, |i, l| l + 26 - k[i % k.len()])
+}
+
+/// affine_encrypt -- letter x becomes a * x + b mod 26
+/// Parameters: plaintext (&str) - message
+///             a (u8) - multiplier, must be coprime w/ 26
+///             b (u8) - shift
+/// Return: String - ciphertext
+pub fn affine_encrypt(plaintext: &str, a: u8, b: u8) -> String {
+    mod_inverse_26(a % 26);
+    map_letters(plaintext, |_, l| ((a as u32 % 26 * l as u32 + b as u32 % 26) % 26) as u8)
+}
+
+/// affine_decrypt -- letter y becomes a^-1 * (y - b) mod 26
+/// Parameters: ciphertext (&str) - encrypted message
+///             a (u8) - multiplier, must be coprime w/ 26
+///             b (u8) - shift
+/// Return: String - plaintext
+pub fn affine_decrypt(ciphertext: &str, a: u8, b: u8) -> String {
+    let inverse = mod_inverse_26(a % 26) as u32;
+    map_letters(ciphertext, |_, l| ((inverse * (l as u32 + 26 - b as u32 % 26)) % 26) as u8)
+}
+
+/// substitution_encrypt -- replace the nth letter of the alphabet w/ the nth letter of the key
+/// Parameters: plaintext (&str) - message
+///             key (&str) - 26 letters, each letter exactly once
+/// Return: String - ciphertext
+pub fn substitution_encrypt(plaintext: &str, key: &str) -> String {
+    let k = parse_alphabet(key);
+    map_letters(plaintext, |_, l| k[l as usize])
+}
+
+/// substitution_decrypt -- reverse of substitution_encrypt
+/// Parameters: ciphertext (&str) - encrypted message
+///             key (&str) - 26 letters, each letter exactly once
+/// Return: String - plaintext
+pub fn substitution_decrypt(ciphertext: &str, key: &str) -> String {
+    let k = parse_alphabet(key);
+    let mut inverse = [0u8; 26];
+    for (i, l) in k.iter().enumerate() {
+        inverse[*l as usize] = i as u8;
+    }
+    map_letters(ciphertext, |_, l| inverse[l as usize])
+}
+
+/// quadgram_score -- log10 probability of the letters under the quadgram table
+/// Parameters: letters (&[u8]) - letter numbers (A = 0)
+///             quadgrams (&[f64]) - table from freq::get_quadgram_table
+/// Return: f64 - score, higher (closer to 0) is more english looking
+pub fn quadgram_score(letters: &[u8], quadgrams: &[f64]) -> f64 {
+    letters
+        .windows(4)
+        .map(|w| {
+            quadgrams[((w[0] as usize * 26 + w[1] as usize) * 26 + w[2] as usize) * 26
+                + w[3] as usize]
+        })
+        .sum()
+}
+
+/// chi_squared_english -- chi-squared statistic of the letter counts against english
+/// Parameters: letters (&[u8]) - letter numbers (A = 0)
+///             expected (&[f64]) - table from freq::get_letter_freq_table
+/// Return: f64 - statistic, lower is more english looking
+fn chi_squared_english(letters: &[u8], expected: &[f64]) -> f64 {
+    let mut counts = [0f64; 26];
+    for l in letters {
+        counts[*l as usize] += 1.0;
+    }
+    let n = letters.len() as f64;
+    counts
+        .iter()
+        .zip(expected.iter())
+        .map(|(c, e)| (c - e * n) * (c - e * n) / (e * n))
+        .sum()
+}
+
+/// break_caesar -- try every shift and keep the most english looking plaintext
+/// Parameters: ciphertext (&str) - encrypted message
+///             quadgrams (&[f64]) - table from freq::get_quadgram_table
+/// Return: (u8, String) - (shift, plaintext)
+pub fn break_caesar(ciphertext: &str, quadgrams: &[f64]) -> (u8, String) {
+    let expected = freq::get_letter_freq_table();
+    let c = letters(ciphertext);
+
+    // quadgrams need at least four letters, letter frequencies work on anything
+    let shift = (0u8..26u8)
+        .map(|shift| {
+            let p: Vec<u8> = c.iter().map(|l| (l + 26 - shift) % 26).collect();
+            let score = if p.len() >= 4 {
+                quadgram_score(&p, quadgrams)
+            } else {
+                -chi_squared_english(&p, &expected)
+            };
+            (shift, score)
+        })
+        .fold((0u8, f64::NEG_INFINITY), |best, s| if s.1 > best.1 { s } else { best })
+        .0;
+    (shift, caesar_decrypt(ciphertext, shift))
+}
+
+/// break_vigenere -- break vigenere w/ the repeating-key machinery
+/// Step 1: rank key lengths by the index of coincidence of the letters
+/// Step 2: solve every column of the top key lengths as caesar (chi-squared)
+/// Step 3: keep the key w/ the best quadgram score
+/// Parameters: ciphertext (&str) - encrypted message
+///             max_keysize (usize) - longest key to try
+///             quadgrams (&[f64]) - table from freq::get_quadgram_table
+/// Return: (String, String) - (key, plaintext)
+pub fn break_vigenere(ciphertext: &str, max_keysize: usize, quadgrams: &[f64]) -> (String, String) {
+    let expected = freq::get_letter_freq_table();
+    let c = letters(ciphertext);
+    if c.is_empty() {
+        panic!("Error: cannot break a ciphertext without letters");
+    }
+
+    let max_keysize = max_keysize.min(c.len() / 2).max(1);
+    let keysizes = xor::estimate_keysize(
+        &Storage::new_init_vec(&c, "ascii"),
+        1,
+        max_keysize,
+        xor::KeySizeMethod::IndexOfCoincidence,
+    );
+
+    let mut best: (Vec<u8>, f64) = (Vec::new(), f64::NEG_INFINITY);
+    for &(keysize, _) in keysizes.iter().take(3) {
+        let key: Vec<u8> = (0..keysize)
+            .map(|column| {
+                let col: Vec<u8> = c.iter().skip(column).step_by(keysize).cloned().collect();
+                (0u8..26u8)
+                    .map(|shift| {
+                        let p: Vec<u8> = col.iter().map(|l| (l + 26 - shift) % 26).collect();
+                        (shift, chi_squared_english(&p, &expected))
+                    })
+                    .fold((0u8, f64::INFINITY), |b, s| if s.1 < b.1 { s } else { b })
+                    .0
+            })
+            .collect();
+
+        let p: Vec<u8> = c
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (l + 26 - key[i % keysize]) % 26)
+            .collect();
+        let score = quadgram_score(&p, quadgrams);
+        if score > best.1 || (score == best.1 && keysize < best.0.len()) {
+            best = (key, score);
+        }
+    }
+
+    let key: String = best.0.iter().map(|l| (b'A' + l) as char).collect();
+    let plaintext = vigenere_decrypt(ciphertext, &key);
+    (key, plaintext)
+}
+
+/// break_affine -- try all 312 affine keys and keep the best quadgram score
+/// Parameters: ciphertext (&str) - encrypted message
+///             quadgrams (&[f64]) - table from freq::get_quadgram_table
+/// Return: (u8, u8, String) - (a, b, plaintext)
+pub fn break_affine(ciphertext: &str, quadgrams: &[f64]) -> (u8, u8, String) {
+    let c = letters(ciphertext);
+
+    let mut best: (u8, u8, f64) = (1, 0, f64::NEG_INFINITY);
+    for a in (1u8..26u8).filter(|a| gcd(*a as u32, 26) == 1) {
+        let inverse = mod_inverse_26(a) as u32;
+        for b in 0u8..26u8 {
+            let p: Vec<u8> = c
+                .iter()
+                .map(|l| ((inverse * (*l as u32 + 26 - b as u32)) % 26) as u8)
+                .collect();
+            let score = quadgram_score(&p, quadgrams);
+            if score > best.2 {
+                best = (a, b, score);
+            }
+        }
+    }
+    (best.0, best.1, affine_decrypt(ciphertext, best.0, best.1))
+}
+
+/// climb -- hill climb a decryption key (cipher letter -> plain letter) by swapping two
+/// letters at a time, keeping every swap that improves the quadgram score
+/// Parameters: c (&[u8]) - ciphertext letter numbers
+///             key (Vec<u8>) - starting decryption key
+///             quadgrams (&[f64]) - table from freq::get_quadgram_table
+/// Return: (Vec<u8>, f64) - (decryption key, score)
+fn climb(c: &[u8], key: Vec<u8>, quadgrams: &[f64]) -> (Vec<u8>, f64) {
+    let mut key = key;
+    let decrypt = |k: &[u8]| -> Vec<u8> { c.iter().map(|l| k[*l as usize]).collect() };
+    let mut score = quadgram_score(&decrypt(&key), quadgrams);
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..26 {
+            for j in (i + 1)..26 {
+                key.swap(i, j);
+                let s = quadgram_score(&decrypt(&key), quadgrams);
+                if s > score {
+                    score = s;
+                    improved = true;
+                } else {
+                    key.swap(i, j);
+                }
+            }
+        }
+    }
+    (key, score)
+}
+
+/// break_substitution -- break a monoalphabetic substitution by hill climbing on quadgram
+/// scores.  The first climb starts from the letter frequency order, every restart starts
+/// from a random key and the best key overall is kept.
+/// Parameters: ciphertext (&str) - encrypted message
+///             restarts (usize) - number of random restarts
+///             quadgrams (&[f64]) - table from freq::get_quadgram_table
+///             rng (&mut Rng) - source of the random restarts
+/// Return: (String, String) - (substitution key as used by substitution_encrypt, plaintext)
+pub fn break_substitution<R: Rng>(
+    ciphertext: &str,
+    restarts: usize,
+    quadgrams: &[f64],
+    rng: &mut R,
+) -> (String, String) {
+    let c = letters(ciphertext);
+
+    // frequency order start -- most common cipher letter -> E, next -> T, ...
+    let counts = analysis::histogram(&Storage::new_init_vec(&c, "ascii"));
+    let mut order: Vec<u8> = (0u8..26u8).collect();
+    order.sort_by(|l, r| counts[*r as usize].cmp(&counts[*l as usize]).then(l.cmp(r)));
+    let mut start = vec![0u8; 26];
+    for (i, l) in order.iter().enumerate() {
+        start[*l as usize] = ETAOIN[i] - b'A';
+    }
+
+    let mut best = climb(&c, start, quadgrams);
+    for _ in 0..restarts {
+        let mut key: Vec<u8> = (0u8..26u8).collect();
+        key.shuffle(rng);
+        let candidate = climb(&c, key, quadgrams);
+        if candidate.1 > best.1 {
+            best = candidate;
+        }
+    }
+
+    // decryption key (cipher -> plain) to encryption key (plain -> cipher)
+    let mut key = [0u8; 26];
+    for (cipher, plain) in best.0.iter().enumerate() {
+        key[*plain as usize] = b'A' + cipher as u8;
+    }
+    let key: String = key.iter().map(|l| *l as char).collect();
+    let plaintext = substitution_decrypt(ciphertext, &key);
+    (key, plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // not part of input_files/english.txt so the breakers are not tested on their training data
+    const PLAINTEXT: &str = "The old lighthouse keeper climbed the narrow stairs every evening \
+        just before sunset, carrying a small lamp and a thermos of strong black coffee. From the \
+        gallery at the top he could watch the fishing boats returning to the harbour, their \
+        engines coughing as they rounded the breakwater. Most nights were quiet, but when the \
+        winter storms arrived he would stay awake until dawn, listening to the waves hammer \
+        against the rocks below and wondering how many more seasons the tower could survive.";
+
+    // TEST caesar
+    #[test]
+    fn check_caesar() {
+        assert_eq!("Khoor, Zruog!", caesar_encrypt("Hello, World!", 3));
+        assert_eq!("Hello, World!", caesar_decrypt("Khoor, Zruog!", 3));
+        assert_eq!("Hello, World!", caesar_decrypt(&caesar_encrypt("Hello, World!", 29), 29));
+    }
+
+    #[test]
+    fn check_break_caesar() {
+        let (shift, plaintext) = break_caesar(&caesar_encrypt(PLAINTEXT, 11), &freq::get_quadgram_table());
+        assert_eq!(11, shift);
+        assert_eq!(PLAINTEXT, plaintext);
+    }
+
+    // TEST vigenere
+    #[test]
+    fn check_vigenere() {
+        assert_eq!("LXFOPV EF RNHR", vigenere_encrypt("ATTACK AT DAWN", "LEMON"));
+        assert_eq!("attack at dawn", vigenere_decrypt("lxfopv ef rnhr", "lemon"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_vigenere_key() {
+        vigenere_encrypt("ATTACK AT DAWN", "LEM0N");
+    }
+
+    #[test]
+    fn check_break_vigenere() {
+        let quadgrams = freq::get_quadgram_table();
+        let (key, plaintext) = break_vigenere(&vigenere_encrypt(PLAINTEXT, "HARBOUR"), 20, &quadgrams);
+        assert_eq!("HARBOUR", key);
+        assert_eq!(PLAINTEXT, plaintext);
+    }
+
+    // TEST affine
+    #[test]
+    fn check_affine() {
+        assert_eq!("Ihhwvc Swfrcp", affine_encrypt("Affine Cipher", 5, 8));
+        assert_eq!("Affine Cipher", affine_decrypt("Ihhwvc Swfrcp", 5, 8));
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_affine_multiplier() {
+        affine_encrypt("Affine Cipher", 13, 8);
+    }
+
+    #[test]
+    fn check_break_affine() {
+        let (a, b, plaintext) = break_affine(&affine_encrypt(PLAINTEXT, 7, 20), &freq::get_quadgram_table());
+        assert_eq!((7, 20), (a, b));
+        assert_eq!(PLAINTEXT, plaintext);
+    }
+
+    // TEST substitution
+    #[test]
+    fn check_substitution() {
+        let key = "QWERTYUIOPASDFGHJKLZXCVBNM";
+        assert_eq!("Itssg, Vgksr!", substitution_encrypt("Hello, World!", key));
+        assert_eq!("Hello, World!", substitution_decrypt("Itssg, Vgksr!", key));
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_substitution_key() {
+        substitution_encrypt("Hello, World!", "QWERTYUIOPASDFGHJKLZXCVBNQ");
+    }
+
+    #[test]
+    fn check_break_substitution() {
+        let key = "PHQGIUMEAYLNOFDXJKRCVSTZWB";
+        let ciphertext = substitution_encrypt(PLAINTEXT, key);
+        let quadgrams = freq::get_quadgram_table();
+        let mut rng = StdRng::seed_from_u64(31);
+        let (_, plaintext) = break_substitution(&ciphertext, 20, &quadgrams, &mut rng);
+
+        // letters that never appear (or appear once) can come out wrong, so compare letters
+        let correct = plaintext
+            .chars()
+            .zip(PLAINTEXT.chars())
+            .filter(|(l, r)| l == r)
+            .count();
+        assert!(correct * 100 >= PLAINTEXT.len() * 98, "{}", plaintext);
+    }
+}
@@ -0,0 +1,278 @@
+//! streaming conversion between the encodings Storage understands (ascii, hex and base64)
+
+use storage::Storage;
+
+use std::io::{Read, Write};
+
+/// CHUNK -- number of bytes read from the input at a time
+const CHUNK: usize = 64 * 1024;
+
+/// ConvertOptions -- how the input is parsed and the output is written
+/// wrap (usize) - characters per output line for hex and base64 (0 writes one line)
+/// strict (bool) - panic on characters outside the input alphabet and on a truncated final
+///                 group instead of skipping them.  Whitespace is always skipped.
+pub struct ConvertOptions {
+    pub wrap: usize,
+    pub strict: bool,
+}
+
+/// group_size -- number of encoded characters that decode to whole bytes
+fn group_size(data_type: &str) -> usize {
+    match data_type {
+        "hex" => 2,
+        "base64" => 4,
+        _ => 1,
+    }
+}
+
+/// is_digit -- true when c belongs to the alphabet of the encoding
+fn is_digit(c: u8, data_type: &str) -> bool {
+    match data_type {
+        "hex" => c.is_ascii_hexdigit(),
+        "base64" => c.is_ascii_alphanumeric() || c == b'+' || c == b'/' || c == b'=',
+        _ => true,
+    }
+}
+
+/// decode -- decode whole groups of hex or base64 characters into raw bytes
+/// A base64 group w/ padding ends a message, so every padded group is decoded on its own.
+/// Parameters: digits (&[u8]) - encoded characters (a multiple of the group size)
+///             data_type (&str) - encoding of the characters
+/// Return: Vec<u8> - raw bytes
+fn decode(digits: &[u8], data_type: &str) -> Vec<u8> {
+    let size = group_size(data_type);
+    let mut out: Vec<u8> = Vec::new();
+    let mut start = 0;
+    while start < digits.len() {
+        let end = match digits[start..].iter().position(|d| *d == b'=') {
+            Some(p) => start + (p / size + 1) * size,
+            None => digits.len(),
+        };
+        let text: String = digits[start..end].iter().map(|d| *d as char).collect();
+        let mut s = Storage::new_init(&text, data_type);
+        s.change_base("ascii");
+        out.extend_from_slice(s.get_data());
+        start = end;
+    }
+    out
+}
+
+/// Wrapped -- writer that breaks text output into lines of a fixed width
+struct Wrapped<'a, W: Write + 'a> {
+    out: &'a mut W,
+    wrap: usize,
+    column: usize,
+}
+
+impl<'a, W: Write> Wrapped<'a, W> {
+    fn write_text(&mut self, text: &str) {
+        for c in text.bytes() {
+            if self.wrap > 0 && self.column == self.wrap {
+                self.out.write_all(b"\n").expect("Error: Unable to write output");
+                self.column = 0;
+            }
+            self.out.write_all(&[c]).expect("Error: Unable to write output");
+            self.column += 1;
+        }
+    }
+}
+
+/// encode -- write raw bytes in the output encoding
+/// Parameters: bytes (&[u8]) - raw bytes (for base64 a multiple of 3 unless it is the end)
+///             data_type (&str) - output encoding
+///             out (&mut Wrapped<W>) - destination
+/// Return: void
+fn encode<W: Write>(bytes: &[u8], data_type: &str, out: &mut Wrapped<W>) {
+    if bytes.is_empty() {
+        return;
+    }
+    if data_type == "ascii" {
+        out.out.write_all(bytes).expect("Error: Unable to write output");
+        return;
+    }
+    let mut s = Storage::new_init_vec(bytes, "ascii");
+    s.change_base(data_type);
+    out.write_text(&s.to_string());
+}
+
+/// convert -- stream data from one encoding to another
+/// Parameters: input (R) - source of the encoded data
+///             output (&mut W) - destination of the converted data
+///             from (&str) - input encoding (ascii, hex or base64)
+///             to (&str) - output encoding (ascii, hex or base64)
+///             options (&ConvertOptions) - line wrapping and strict/lenient parsing
+/// Return: u64 - number of raw bytes converted
+pub fn convert<R: Read, W: Write>(
+    mut input: R,
+    output: &mut W,
+    from: &str,
+    to: &str,
+    options: &ConvertOptions,
+) -> u64 {
+    for t in &[from, to] {
+        if *t != "ascii" && *t != "hex" && *t != "base64" {
+            panic!("Error: invalid type ({})", t);
+        }
+    }
+
+    let size = group_size(from);
+    let mut out = Wrapped {
+        out: output,
+        wrap: if to == "ascii" { 0 } else { options.wrap },
+        column: 0,
+    };
+    let mut buffer = vec![0u8; CHUNK];
+    let mut digits: Vec<u8> = Vec::new(); // encoded characters that do not fill a group yet
+    let mut bytes: Vec<u8> = Vec::new(); // raw bytes that do not fill an output group yet
+    let mut offset: u64 = 0;
+    let mut total: u64 = 0;
+
+    loop {
+        let n = input.read(&mut buffer).expect("Error: Unable to read input");
+        if n == 0 {
+            break;
+        }
+
+        if from == "ascii" {
+            bytes.extend_from_slice(&buffer[..n]);
+        } else {
+            for (i, c) in buffer[..n].iter().enumerate() {
+                if c.is_ascii_whitespace() {
+                    continue;
+                }
+                if is_digit(*c, from) {
+                    digits.push(*c);
+                } else if options.strict {
+                    panic!(
+                        "Error: invalid {} character {:?} at byte {}",
+                        from,
+                        *c as char,
+                        offset + i as u64
+                    );
+                }
+            }
+            let whole = digits.len() - digits.len() % size;
+            bytes.extend(decode(&digits[..whole], from));
+            digits.drain(..whole);
+        }
+        offset += n as u64;
+
+        // base64 output needs whole groups of 3 bytes until the end of the input
+        let whole = if to == "base64" { bytes.len() - bytes.len() % 3 } else { bytes.len() };
+        encode(&bytes[..whole], to, &mut out);
+        total += whole as u64;
+        bytes.drain(..whole);
+    }
+
+    // truncated final group
+    if !digits.is_empty() {
+        if options.strict {
+            panic!("Error: input ends in the middle of a {} group", from);
+        }
+        if from == "base64" && digits.len() > 1 {
+            while digits.len() < 4 {
+                digits.push(b'=');
+            }
+            bytes.extend(decode(&digits, from));
+        }
+    }
+    encode(&bytes, to, &mut out);
+    total += bytes.len() as u64;
+
+    if to != "ascii" && (total > 0 || out.column > 0) {
+        out.out.write_all(b"\n").expect("Error: Unable to write output");
+    }
+    out.out.flush().expect("Error: Unable to write output");
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trickle -- reader that returns one byte per read to test group boundaries
+    struct Trickle<'a> {
+        data: &'a [u8],
+    }
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> ::std::io::Result<usize> {
+            if self.data.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.data[0];
+            self.data = &self.data[1..];
+            Ok(1)
+        }
+    }
+
+    fn run(input: &str, from: &str, to: &str, wrap: usize, strict: bool) -> String {
+        let mut out: Vec<u8> = Vec::new();
+        let options = ConvertOptions { wrap, strict };
+        convert(input.as_bytes(), &mut out, from, to, &options);
+        String::from_utf8(out).unwrap()
+    }
+
+    const HEX: &str = "49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d";
+    const BASE64: &str = "SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t";
+
+    // TEST convert
+    #[test]
+    fn check_convert_pairs() {
+        let ascii = "I'm killing your brain like a poisonous mushroom";
+        assert_eq!(format!("{}\n", BASE64), run(HEX, "hex", "base64", 0, true));
+        assert_eq!(format!("{}\n", HEX), run(BASE64, "base64", "hex", 0, true));
+        assert_eq!(ascii, run(HEX, "hex", "ascii", 0, true));
+        assert_eq!(ascii, run(BASE64, "base64", "ascii", 0, true));
+        assert_eq!(format!("{}\n", HEX), run(ascii, "ascii", "hex", 0, true));
+        assert_eq!(format!("{}\n", BASE64), run(ascii, "ascii", "base64", 0, true));
+        assert_eq!(ascii, run(ascii, "ascii", "ascii", 0, true));
+    }
+
+    #[test]
+    fn check_convert_padding() {
+        assert_eq!("YQ==\n", run("a", "ascii", "base64", 0, true));
+        assert_eq!("YWI=\n", run("ab", "ascii", "base64", 0, true));
+        assert_eq!("ab", run("YWI=", "base64", "ascii", 0, true));
+        // two padded messages back to back
+        assert_eq!("aab", run("YQ==\nYWI=\n", "base64", "ascii", 0, true));
+    }
+
+    #[test]
+    fn check_convert_streaming() {
+        let mut out: Vec<u8> = Vec::new();
+        let options = ConvertOptions { wrap: 0, strict: true };
+        let n = convert(Trickle { data: HEX.as_bytes() }, &mut out, "hex", "base64", &options);
+        assert_eq!(48, n);
+        assert_eq!(format!("{}\n", BASE64), String::from_utf8(out).unwrap());
+
+        let mut out: Vec<u8> = Vec::new();
+        convert(Trickle { data: b"Zm9vYmFy\nYmF6" }, &mut out, "base64", "ascii", &options);
+        assert_eq!(b"foobarbaz".to_vec(), out);
+    }
+
+    #[test]
+    fn check_convert_wrap() {
+        assert_eq!("SSdtIGtp\nbGxpbmcg\neW91ciBi\n", run(&HEX[..36], "hex", "base64", 8, true));
+        assert_eq!("6162\n63\n", run("abc", "ascii", "hex", 4, true));
+    }
+
+    #[test]
+    fn check_convert_lenient() {
+        assert_eq!("hi", run("68:69", "hex", "ascii", 0, false));
+        assert_eq!("h", run("686", "hex", "ascii", 0, false));
+        assert_eq!("ab", run("YW-I", "base64", "ascii", 0, false));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid hex character ':' at byte 2")]
+    fn check_convert_strict_character() {
+        run("68:69", "hex", "ascii", 0, true);
+    }
+
+    #[test]
+    #[should_panic(expected = "middle of a hex group")]
+    fn check_convert_strict_truncated() {
+        run("686", "hex", "ascii", 0, true);
+    }
+}
@@ -0,0 +1,236 @@
+//! crib dragging on ciphertexts that share a keystream
+
+use keystream::{is_printable, Keystream};
+use storage::Storage;
+use xor;
+
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+/// CribPosition -- result of placing a crib at one offset
+/// offset (usize) - position of the crib in the ciphertext
+/// plaintexts (Vec<Option<Storage>>) - implied plaintext of every ciphertext at the offset
+///                                     (ascii), None when the ciphertext ends before the offset
+/// printable (bool) - true if every implied plaintext is printable ascii
+pub struct CribPosition {
+    pub offset: usize,
+    pub plaintexts: Vec<Option<Storage>>,
+    pub printable: bool,
+}
+
+/// drag -- slide a crib across every offset of one ciphertext and compute the plaintext
+/// that the implied keystream gives every ciphertext at the same offset
+/// Parameters: ciphertexts (&[Storage]) - ciphertexts sharing a keystream (ascii)
+///             index (usize) - ciphertext the crib is placed in
+///             crib (&Storage) - guessed plaintext (ascii)
+/// Return: Vec<CribPosition> - one entry per offset
+pub fn drag(ciphertexts: &[Storage], index: usize, crib: &Storage) -> Vec<CribPosition> {
+    if index >= ciphertexts.len() {
+        panic!("Error: there is no ciphertext {}", index);
+    }
+    let cipher = &ciphertexts[index];
+    if crib.is_empty() || crib.len() > cipher.len() {
+        return Vec::new();
+    }
+
+    (0..=(cipher.len() - crib.len()))
+        .map(|offset| {
+            let segment = &cipher.index(offset, offset + crib.len()) ^ crib;
+            let plaintexts: Vec<Option<Storage>> = ciphertexts
+                .iter()
+                .map(|c| {
+                    if offset >= c.len() {
+                        None
+                    } else {
+                        let end = c.len().min(offset + crib.len());
+                        Some(xor::xor_keystream(&c.index(offset, end), &segment))
+                    }
+                })
+                .collect();
+            let printable = plaintexts.iter().all(|p| match p {
+                Some(p) => p.get_data().iter().all(|b| is_printable(*b)),
+                None => true,
+            });
+
+            CribPosition {
+                offset,
+                plaintexts,
+                printable,
+            }
+        })
+        .collect()
+}
+
+/// print_help -- print the commands understood by interactive
+fn print_help() {
+    println!("Commands:");
+    println!("  show                      decrypt every ciphertext w/ the keystream");
+    println!("  drag <n> <crib>           slide crib across ciphertext n (printable results only)");
+    println!("  dragall <n> <crib>        slide crib across ciphertext n (every offset)");
+    println!("  guess <n> <offset> <text> commit text at offset of ciphertext n to the keystream");
+    println!("  clear <offset> [length]   forget keystream bytes");
+    println!("  auto                      fill unknown keystream bytes w/ a statistical guess");
+    println!("  save [file]               write the keystream to disk");
+    println!("  help                      show this message");
+    println!("  quit                      save the keystream to the --keystream file and exit");
+}
+
+/// print_ciphertexts -- print every ciphertext decrypted w/ the keystream
+fn print_ciphertexts(ciphertexts: &[Storage], keystream: &Keystream) {
+    println!(
+        "Keystream ({} of {} bytes known): {}",
+        keystream.known(),
+        keystream.len(),
+        keystream
+    );
+    for (i, c) in ciphertexts.iter().enumerate() {
+        println!("{:>3}: {}", i, keystream.decrypt(c));
+    }
+}
+
+/// print_drag -- print the result of drag
+fn print_drag(positions: &Vec<CribPosition>, only_printable: bool) {
+    for p in positions {
+        if only_printable && !p.printable {
+            continue;
+        }
+        println!("offset {}:", p.offset);
+        for (i, plaintext) in p.plaintexts.iter().enumerate() {
+            if let Some(plaintext) = plaintext {
+                let shown: String = plaintext
+                    .get_data()
+                    .iter()
+                    .map(|b| if is_printable(*b) { *b as char } else { '.' })
+                    .collect();
+                println!("  {:>3}: {:?}", i, shown);
+            }
+        }
+    }
+}
+
+/// interactive -- crib dragging session reading commands from stdin
+/// Parameters: ciphertexts (&[Storage]) - ciphertexts sharing a keystream (ascii)
+///             keystream (Keystream) - starting keystream
+///             filename (&str) - default file used by save
+/// Return: Keystream - keystream when the session ended
+pub fn interactive(ciphertexts: &[Storage], keystream: Keystream, filename: &str) -> Keystream {
+    let mut keystream = keystream;
+    let length = ciphertexts.iter().map(|c| c.len()).max().unwrap_or(0);
+    if keystream.len() < length {
+        keystream.resize(length);
+    }
+
+    print_help();
+    print_ciphertexts(ciphertexts, &keystream);
+
+    let stdin = io::stdin();
+    loop {
+        print!("crib> ");
+        io::stdout().flush().expect("Error: Unable to write to stdout");
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).expect("Error: Unable to read stdin") == 0 {
+            break;
+        }
+        let line = line.trim_end_matches(['\n', '\r']);
+        let mut words = line.splitn(2, ' ');
+        let command = words.next().unwrap_or("");
+        let rest = words.next().unwrap_or("");
+
+        match command {
+            "" => (),
+            "show" => print_ciphertexts(ciphertexts, &keystream),
+            "drag" | "dragall" => {
+                let mut args = rest.splitn(2, ' ');
+                match (args.next().and_then(|n| n.parse::<usize>().ok()), args.next()) {
+                    (Some(n), Some(crib)) if n < ciphertexts.len() => {
+                        let positions = drag(ciphertexts, n, &Storage::new_init(crib, "ascii"));
+                        print_drag(&positions, command == "drag");
+                    }
+                    _ => println!("Usage: {} <n> <crib>", command),
+                }
+            }
+            "guess" => {
+                let mut args = rest.splitn(3, ' ');
+                match (
+                    args.next().and_then(|n| n.parse::<usize>().ok()),
+                    args.next().and_then(|o| o.parse::<usize>().ok()),
+                    args.next(),
+                ) {
+                    (Some(n), Some(offset), Some(text))
+                        if n < ciphertexts.len() && offset + text.len() <= ciphertexts[n].len() =>
+                    {
+                        keystream.commit(&ciphertexts[n], offset, &Storage::new_init(text, "ascii"));
+                        print_ciphertexts(ciphertexts, &keystream);
+                    }
+                    _ => println!("Usage: guess <n> <offset> <text> (text must fit in ciphertext n)"),
+                }
+            }
+            "clear" => {
+                let args: Vec<usize> = rest.split_whitespace().filter_map(|a| a.parse().ok()).collect();
+                match args.len() {
+                    1 | 2 => {
+                        let count = if args.len() == 2 { args[1] } else { 1 };
+                        keystream.clear(args[0], count);
+                        print_ciphertexts(ciphertexts, &keystream);
+                    }
+                    _ => println!("Usage: clear <offset> [length]"),
+                }
+            }
+            "auto" => {
+                let guess = xor::break_many_time_pad(ciphertexts);
+                for (i, b) in guess.keystream.get_data().iter().enumerate() {
+                    if keystream.get(i).is_none() {
+                        keystream.set(i, Some(*b));
+                    }
+                }
+                print_ciphertexts(ciphertexts, &keystream);
+            }
+            "save" => {
+                let file = if rest.trim().is_empty() { filename } else { rest.trim() };
+                keystream.save(file);
+                println!("Saved keystream to {}", file);
+            }
+            "help" => print_help(),
+            "quit" | "exit" => break,
+            _ => println!("Unknown command {}, type help for a list of commands", command),
+        }
+    }
+    keystream
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ciphertexts() -> (Storage, Vec<Storage>) {
+        let k: Vec<u8> = (0..40).map(|i| ((i * 89 + 7) % 256) as u8).collect();
+        let k = Storage::new_init_vec(&k, "ascii");
+        let c = ["attack at dawn", "the quick brown fox", "meet me at noon"]
+            .iter()
+            .map(|p| xor::xor_keystream(&Storage::new_init(p, "ascii"), &k))
+            .collect();
+        (k, c)
+    }
+
+    // TEST drag
+    #[test]
+    fn check_drag() {
+        let (_, c) = ciphertexts();
+        let positions = drag(&c, 1, &Storage::new_init("quick", "ascii"));
+        assert_eq!(15, positions.len());
+
+        let hit = &positions[4];
+        assert_eq!(4, hit.offset);
+        assert!(hit.printable);
+        assert_eq!("ck at", hit.plaintexts[0].as_ref().unwrap().to_string());
+        assert_eq!("quick", hit.plaintexts[1].as_ref().unwrap().to_string());
+        assert_eq!(" me a", hit.plaintexts[2].as_ref().unwrap().to_string());
+
+        // ciphertext 0 is shorter than the crib at the last offset
+        let last = &positions[14];
+        assert_eq!(None, last.plaintexts[0].as_ref().map(|p| p.len()));
+        assert_eq!(Some(1), last.plaintexts[2].as_ref().map(|p| p.len()));
+    }
+}
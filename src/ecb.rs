@@ -0,0 +1,549 @@
+//! attacks on AES-128 in ECB mode and the oracles they are run against
+
+use aes::{self, Mode, BLOCK};
+use profile;
+use storage::Storage;
+
+use rand::Rng;
+
+use std::cell::Cell;
+
+/// random_bytes -- helper function for random keys, ivs and junk
+/// Parameters: rng (&mut Rng) - source of randomness
+///             n (usize) - number of bytes
+/// Return: Vec<u8> - random bytes
+pub fn random_bytes<R: Rng>(rng: &mut R, n: usize) -> Vec<u8> {
+    (0..n).map(|_| rng.gen::<u8>()).collect()
+}
+
+/// ModeOracle -- encrypts attacker chosen input under a random key, w/ 5-10 random bytes
+/// before and after it and PKCS#7 padding, in ECB or CBC mode (a coin flip) [challenge 11]
+/// key (Storage) - random 16 byte key
+/// iv (Storage) - random 16 byte iv (only used in CBC mode)
+/// mode (Mode) - Ecb or Cbc
+/// prefix (Vec<u8>) - random bytes added before the input
+/// suffix (Vec<u8>) - random bytes added after the input
+pub struct ModeOracle {
+    key: Storage,
+    iv: Storage,
+    mode: Mode,
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+}
+
+impl ModeOracle {
+    /// random -- oracle w/ a random key, mode, prefix and suffix
+    pub fn random<R: Rng>(rng: &mut R) -> ModeOracle {
+        let prefix = rng.gen_range(5..11);
+        let suffix = rng.gen_range(5..11);
+        ModeOracle {
+            key: Storage::new_init_vec(&random_bytes(rng, BLOCK), "ascii"),
+            iv: Storage::new_init_vec(&random_bytes(rng, BLOCK), "ascii"),
+            mode: if rng.gen::<bool>() { Mode::Ecb } else { Mode::Cbc },
+            prefix: random_bytes(rng, prefix),
+            suffix: random_bytes(rng, suffix),
+        }
+    }
+
+    pub fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// encrypt -- encrypt prefix || input || suffix
+    /// Parameters: input (&[u8]) - attacker chosen plaintext
+    /// Return: Vec<u8> - ciphertext
+    pub fn encrypt(&self, input: &[u8]) -> Vec<u8> {
+        let mut plaintext = self.prefix.clone();
+        plaintext.extend_from_slice(input);
+        plaintext.extend_from_slice(&self.suffix);
+        let padded = aes::pkcs7_pad(&Storage::new_init_vec(&plaintext, "ascii"), BLOCK);
+        let cipher = match self.mode {
+            Mode::Ecb => aes::ecb_encrypt(&padded, &self.key),
+            _ => aes::cbc_encrypt(&padded, &self.key, &self.iv),
+        };
+        cipher.get_data().clone()
+    }
+}
+
+/// chosen_input -- input that puts two equal blocks in the plaintext whatever the prefix is
+/// (up to a block of it fills the first block, the other two blocks are whole)
+/// Parameters: void
+/// Return: Vec<u8> - three blocks of 'A'
+pub fn chosen_input() -> Vec<u8> {
+    vec![b'A'; 3 * BLOCK]
+}
+
+/// detect_mode -- ECB when any ciphertext block repeats (see aes::repeated_blocks), otherwise
+/// CBC.  Only works when the plaintext had equal blocks, e.g. w/ chosen_input().
+/// Parameters: cipher (&[u8]) - ciphertext
+/// Return: Mode - Ecb or Cbc
+pub fn detect_mode(cipher: &[u8]) -> Mode {
+    if aes::repeated_blocks(&Storage::new_init_vec(cipher, "ascii"), BLOCK) > 0 {
+        Mode::Ecb
+    } else {
+        Mode::Cbc
+    }
+}
+
+/// Accuracy -- how often detect_mode guessed the mode of a random oracle
+/// ecb (usize) - trials where the oracle used ECB
+/// cbc (usize) - trials where the oracle used CBC
+/// ecb_correct (usize) - ECB trials detected as ECB
+/// cbc_correct (usize) - CBC trials detected as CBC
+#[derive(Debug, Default, PartialEq)]
+pub struct Accuracy {
+    pub ecb: usize,
+    pub cbc: usize,
+    pub ecb_correct: usize,
+    pub cbc_correct: usize,
+}
+
+impl Accuracy {
+    pub fn trials(&self) -> usize {
+        self.ecb + self.cbc
+    }
+
+    pub fn correct(&self) -> usize {
+        self.ecb_correct + self.cbc_correct
+    }
+
+    /// rate -- fraction of trials detected correctly (0.0 w/o trials)
+    pub fn rate(&self) -> f64 {
+        if self.trials() == 0 {
+            return 0.0;
+        }
+        self.correct() as f64 / self.trials() as f64
+    }
+}
+
+/// measure_detection -- run detect_mode against many random oracles
+/// Parameters: input (&[u8]) - chosen plaintext given to every oracle
+///             trials (usize) - number of random oracles
+///             rng (&mut Rng) - source of randomness for the oracles
+/// Return: Accuracy - trials and correct guesses per mode
+pub fn measure_detection<R: Rng>(input: &[u8], trials: usize, rng: &mut R) -> Accuracy {
+    let mut accuracy = Accuracy::default();
+    for _ in 0..trials {
+        let oracle = ModeOracle::random(rng);
+        let guess = detect_mode(&oracle.encrypt(input));
+        match oracle.get_mode() {
+            Mode::Ecb => {
+                accuracy.ecb += 1;
+                accuracy.ecb_correct += (guess == Mode::Ecb) as usize;
+            }
+            _ => {
+                accuracy.cbc += 1;
+                accuracy.cbc_correct += (guess == Mode::Cbc) as usize;
+            }
+        }
+    }
+    accuracy
+}
+
+/// AppendOracle -- encrypts prefix || attacker chosen input || secret under a fixed key in
+/// ECB mode w/ PKCS#7 padding [challenges 12 and 14]
+/// key (Storage) - 16 byte key
+/// prefix (Vec<u8>) - bytes added before the input (empty in challenge 12)
+/// secret (Vec<u8>) - unknown bytes added after the input
+pub struct AppendOracle {
+    key: Storage,
+    prefix: Vec<u8>,
+    secret: Vec<u8>,
+}
+
+impl AppendOracle {
+    pub fn new(key: Storage, prefix: Vec<u8>, secret: Vec<u8>) -> AppendOracle {
+        AppendOracle {
+            key,
+            prefix,
+            secret,
+        }
+    }
+
+    /// random -- oracle w/ a random key and a random count (0 - max_prefix) of random bytes
+    /// before the input
+    pub fn random<R: Rng>(rng: &mut R, secret: &[u8], max_prefix: usize) -> AppendOracle {
+        let key = Storage::new_init_vec(&random_bytes(rng, BLOCK), "ascii");
+        let prefix = rng.gen_range(0..max_prefix + 1);
+        AppendOracle::new(key, random_bytes(rng, prefix), secret.to_vec())
+    }
+
+    /// encrypt -- encrypt prefix || input || secret
+    /// Parameters: input (&[u8]) - attacker chosen plaintext
+    /// Return: Vec<u8> - ciphertext
+    pub fn encrypt(&self, input: &[u8]) -> Vec<u8> {
+        let mut plaintext = self.prefix.clone();
+        plaintext.extend_from_slice(input);
+        plaintext.extend_from_slice(&self.secret);
+        let padded = aes::pkcs7_pad(&Storage::new_init_vec(&plaintext, "ascii"), BLOCK);
+        aes::ecb_encrypt(&padded, &self.key).get_data().clone()
+    }
+}
+
+/// ByteAtATime -- what the byte-at-a-time attack learned about an oracle
+/// block (usize) - block size of the cipher
+/// prefix_len (usize) - bytes the oracle adds before the input
+/// secret (Vec<u8>) - bytes the oracle adds after the input
+/// queries (usize) - calls made to the oracle
+pub struct ByteAtATime {
+    pub block: usize,
+    pub prefix_len: usize,
+    pub secret: Vec<u8>,
+    pub queries: usize,
+}
+
+/// find_block_size -- grow the input until the ciphertext grows, it grows by a whole block
+/// Parameters: oracle (&Fn(&[u8]) -> Vec<u8>) - encryption oracle
+/// Return: usize - block size
+pub fn find_block_size<F: Fn(&[u8]) -> Vec<u8>>(oracle: &F) -> usize {
+    let base = oracle(&[]).len();
+    for n in 1..257 {
+        let len = oracle(&vec![b'A'; n]).len();
+        if len > base {
+            return len - base;
+        }
+    }
+    panic!("Error: the ciphertext never grew, the oracle doesn't look like a block cipher");
+}
+
+/// is_ecb -- true when three blocks of equal input give repeated ciphertext blocks (at least
+/// two of them are whole blocks whatever the prefix is)
+/// Parameters: oracle (&Fn(&[u8]) -> Vec<u8>) - encryption oracle
+///             block (usize) - block size
+/// Return: bool - the oracle uses ECB
+pub fn is_ecb<F: Fn(&[u8]) -> Vec<u8>>(oracle: &F, block: usize) -> bool {
+    let cipher = oracle(&vec![b'A'; 3 * block]);
+    aes::repeated_blocks(&Storage::new_init_vec(&cipher, "ascii"), block) > 0
+}
+
+/// find_prefix_len -- find how many bytes the oracle adds before the input.  The first block
+/// that changes w/ the input holds its first byte, and growing a filler until that block stops
+/// changing w/ the byte after the filler shows how much of the block the prefix takes.  Only
+/// the block that changes is compared, so repeated blocks in the prefix or the secret can't
+/// be mistaken for the filler.
+/// Parameters: oracle (&Fn(&[u8]) -> Vec<u8>) - ECB encryption oracle
+///             block (usize) - block size
+/// Return: usize - length of the prefix
+pub fn find_prefix_len<F: Fn(&[u8]) -> Vec<u8>>(oracle: &F, block: usize) -> usize {
+    let first_change = |l: &[u8], r: &[u8]| {
+        (0..l.len().min(r.len()) / block)
+            .find(|i| l[i * block..(i + 1) * block] != r[i * block..(i + 1) * block])
+            .expect("Error: the input doesn't change the ciphertext")
+    };
+    let start = first_change(&oracle(b"A"), &oracle(b"B"));
+    for n in 1..block + 1 {
+        let mut l = vec![b'A'; n];
+        let mut r = l.clone();
+        l.push(b'X');
+        r.push(b'Y');
+        if first_change(&oracle(&l), &oracle(&r)) > start {
+            return (start + 1) * block - n;
+        }
+    }
+    panic!("Error: no filler completed the block of the prefix, the oracle doesn't look like ECB");
+}
+
+/// find_secret_len -- grow the input until a whole block of padding is added
+/// Parameters: oracle (&Fn(&[u8]) -> Vec<u8>) - encryption oracle
+///             block (usize) - block size
+///             prefix_len (usize) - length of the prefix
+/// Return: usize - length of the secret
+pub fn find_secret_len<F: Fn(&[u8]) -> Vec<u8>>(oracle: &F, block: usize, prefix_len: usize) -> usize {
+    let base = oracle(&[]).len();
+    let n = (1..block + 1)
+        .find(|n| oracle(&vec![b'A'; *n]).len() > base)
+        .unwrap_or(block);
+    // prefix || n bytes || secret just filled its last block
+    base - prefix_len - n
+}
+
+/// recover_secret -- decrypt the secret one byte at a time.  The filler pushes the next
+/// unknown byte to the end of a block whose other bytes are known, and the same query also
+/// carries every possible block (the last 15 known bytes and each byte value) so one call
+/// per byte is enough.
+/// Parameters: oracle (&Fn(&[u8]) -> Vec<u8>) - ECB encryption oracle
+///             block (usize) - block size
+///             prefix_len (usize) - length of the prefix
+///             secret_len (usize) - length of the secret
+/// Return: Vec<u8> - secret
+pub fn recover_secret<F: Fn(&[u8]) -> Vec<u8>>(
+    oracle: &F,
+    block: usize,
+    prefix_len: usize,
+    secret_len: usize,
+) -> Vec<u8> {
+    // pad the prefix to a whole block, our blocks start at index start
+    let pad = (block - prefix_len % block) % block;
+    let start = (prefix_len + pad) / block;
+
+    let mut known: Vec<u8> = vec![b'A'; block - 1];
+    for i in 0..secret_len {
+        let window = known[known.len() - (block - 1)..].to_vec();
+        let mut input = vec![b'A'; pad];
+        for b in 0..256 {
+            input.extend_from_slice(&window);
+            input.push(b as u8);
+        }
+        input.extend(vec![b'A'; block - 1 - i % block]);
+
+        let cipher = oracle(&input);
+        let at = |n: usize| &cipher[n * block..(n + 1) * block];
+        let target = at(start + 256 + i / block);
+        match (0..256).find(|b| at(start + b) == target) {
+            Some(b) => known.push(b as u8),
+            None => panic!("Error: no byte matched at {} of the secret", i),
+        }
+    }
+    known.split_off(block - 1)
+}
+
+/// byte_at_a_time -- recover the secret an ECB oracle adds after the input
+/// Works on any oracle that encrypts prefix || input || secret w/ ECB and padding, where the
+/// prefix and secret don't change between calls (the prefix may be empty).
+/// Parameters: oracle (&Fn(&[u8]) -> Vec<u8>) - ECB encryption oracle
+/// Return: ByteAtATime - block size, prefix length, secret and number of queries
+pub fn byte_at_a_time<F: Fn(&[u8]) -> Vec<u8>>(oracle: &F) -> ByteAtATime {
+    let queries = Cell::new(0);
+    let counted = |input: &[u8]| {
+        queries.set(queries.get() + 1);
+        oracle(input)
+    };
+
+    let block = find_block_size(&counted);
+    if !is_ecb(&counted, block) {
+        panic!("Error: the oracle doesn't use ECB");
+    }
+    let prefix_len = find_prefix_len(&counted, block);
+    let secret_len = find_secret_len(&counted, block, prefix_len);
+    let secret = recover_secret(&counted, block, prefix_len, secret_len);
+
+    ByteAtATime {
+        block,
+        prefix_len,
+        secret,
+        queries: queries.get(),
+    }
+}
+
+/// ProfileOracle -- encrypts the cookie of a new user under a fixed key in ECB mode w/ PKCS#7
+/// padding and reads cookies back [challenge 13]
+/// key (Storage) - 16 byte key
+/// escape (bool) - quote metacharacters in the email (profile::profile_for) or not
+///                 (profile::profile_for_unescaped)
+pub struct ProfileOracle {
+    key: Storage,
+    escape: bool,
+}
+
+impl ProfileOracle {
+    pub fn new(key: Storage, escape: bool) -> ProfileOracle {
+        ProfileOracle {
+            key,
+            escape,
+        }
+    }
+
+    /// random -- oracle w/ a random key
+    pub fn random<R: Rng>(rng: &mut R, escape: bool) -> ProfileOracle {
+        ProfileOracle::new(Storage::new_init_vec(&random_bytes(rng, BLOCK), "ascii"), escape)
+    }
+
+    /// encrypt -- encrypted cookie of a new user w/ the email
+    /// Parameters: email (&str) - attacker chosen email
+    /// Return: Vec<u8> - ciphertext
+    pub fn encrypt(&self, email: &str) -> Vec<u8> {
+        let cookie = if self.escape {
+            profile::profile_for(email)
+        } else {
+            profile::profile_for_unescaped(email)
+        };
+        let padded = aes::pkcs7_pad(&Storage::new_init(&cookie, "ascii"), BLOCK);
+        aes::ecb_encrypt(&padded, &self.key).get_data().clone()
+    }
+
+    /// decrypt -- decrypt and decode a cookie
+    /// Parameters: cipher (&[u8]) - ciphertext
+    /// Return: Vec<(String, String)> - keys and values of the cookie
+    pub fn decrypt(&self, cipher: &[u8]) -> Vec<(String, String)> {
+        let padded = aes::ecb_decrypt(&Storage::new_init_vec(cipher, "ascii"), &self.key);
+        let cookie = aes::pkcs7_unpad(&padded, BLOCK);
+        profile::decode(&String::from_utf8_lossy(cookie.get_data()))
+    }
+}
+
+/// cut_and_paste -- forge the cookie of an admin from two new user cookies.  The first email
+/// lines "admin" and its padding up as a whole block, the second one ends a block right after
+/// "role=", so its blocks up to there followed by the admin block decrypt to role=admin.
+/// Quoting metacharacters doesn't help since nothing in the first email is a metacharacter,
+/// and the second email is lined up by watching the ciphertext grow, so it works whether the
+/// oracle quotes the email (making it longer) or not.
+/// Parameters: oracle (&Fn(&str) -> Vec<u8>) - encrypts the cookie of a new user (see
+///                                            profile::profile_for) w/ ECB
+///             email (&str) - email of the forged user (padded w/ 'a' at the front to line up)
+/// Return: Vec<u8> - ciphertext of email=...&uid=10&role=admin
+pub fn cut_and_paste<F: Fn(&str) -> Vec<u8>>(oracle: &F, email: &str) -> Vec<u8> {
+    // the cookie is email=EMAIL&uid=10&role=ROLE, find what comes before and after the email
+    let layout = profile::profile_for("");
+    let head = layout.find("&").expect("Error: unexpected profile layout");
+    let tail = layout.rfind("=").expect("Error: unexpected profile layout") + 1 - head;
+
+    // the block after the head is "admin" and its padding
+    let admin = aes::pkcs7_pad(&Storage::new_init("admin", "ascii"), BLOCK);
+    let mut input = "a".repeat((BLOCK - head % BLOCK) % BLOCK);
+    input.push_str(&String::from_utf8_lossy(admin.get_data()));
+    let first = head.div_ceil(BLOCK);
+    let admin_block = oracle(&input)[first * BLOCK..(first + 1) * BLOCK].to_vec();
+
+    // "role=" ends a block.  The cookie fills its last block after k more bytes, then the
+    // role ("user") has to move to a block of its own.
+    let role = layout.len() - head - tail;
+    let base = oracle(email).len();
+    let k = (1..BLOCK + 1)
+        .find(|k| oracle(&format!("{}{}", "a".repeat(*k), email)).len() > base)
+        .expect("Error: the cookie never grew, the oracle doesn't look like ECB");
+    let extra = (k + role) % BLOCK;
+    let cipher = oracle(&format!("{}{}", "a".repeat(extra), email));
+    let mut forged = cipher[..cipher.len() - BLOCK].to_vec();
+    forged.extend(admin_block);
+    forged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // TEST ModeOracle
+    #[test]
+    fn check_mode_oracle() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..20 {
+            let oracle = ModeOracle::random(&mut rng);
+            let cipher = oracle.encrypt(b"");
+            // 10-20 random bytes pad to 2 blocks at most
+            assert!(cipher.len() == BLOCK || cipher.len() == 2 * BLOCK);
+            assert_eq!(0, oracle.encrypt(&chosen_input()).len() % BLOCK);
+        }
+    }
+
+    // TEST detect_mode, measure_detection
+    #[test]
+    fn check_detect_mode() {
+        let mut rng = StdRng::seed_from_u64(12);
+        let accuracy = measure_detection(&chosen_input(), 200, &mut rng);
+        assert_eq!(200, accuracy.trials());
+        assert!(accuracy.ecb > 0 && accuracy.cbc > 0);
+        assert_eq!(200, accuracy.correct());
+        assert_eq!(1.0, accuracy.rate());
+    }
+
+    #[test]
+    fn check_detect_mode_short_input() {
+        // w/o two equal plaintext blocks every oracle looks like CBC
+        let mut rng = StdRng::seed_from_u64(13);
+        let accuracy = measure_detection(b"AAAAAAAAAAAAAAAA", 100, &mut rng);
+        assert_eq!(0, accuracy.ecb_correct);
+        assert_eq!(accuracy.cbc, accuracy.cbc_correct);
+    }
+
+    // TEST byte_at_a_time
+    const SECRET: &[u8] = b"Rollin' in my 5.0\nWith my rag-top down so my hair can blow\n";
+
+    #[test]
+    fn check_byte_at_a_time() {
+        let mut rng = StdRng::seed_from_u64(14);
+        let oracle = AppendOracle::random(&mut rng, SECRET, 0);
+        let found = byte_at_a_time(&|input: &[u8]| oracle.encrypt(input));
+        assert_eq!(BLOCK, found.block);
+        assert_eq!(0, found.prefix_len);
+        assert_eq!(SECRET.to_vec(), found.secret);
+        assert!(found.queries < SECRET.len() + 4 * BLOCK);
+    }
+
+    #[test]
+    fn check_byte_at_a_time_prefix() {
+        let mut rng = StdRng::seed_from_u64(15);
+        for prefix in [1, 15, 16, 17, 40].iter() {
+            let key = Storage::new_init_vec(&random_bytes(&mut rng, BLOCK), "ascii");
+            let oracle = AppendOracle::new(key, random_bytes(&mut rng, *prefix), SECRET.to_vec());
+            let found = byte_at_a_time(&|input: &[u8]| oracle.encrypt(input));
+            assert_eq!(*prefix, found.prefix_len);
+            assert_eq!(SECRET.to_vec(), found.secret);
+        }
+    }
+
+    #[test]
+    fn check_find_prefix_len_filler() {
+        // a prefix ending in 'A' and a secret starting w/ 'A' must not fool the search
+        let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+        let oracle = AppendOracle::new(key, b"xyzAA".to_vec(), b"AAsecret".to_vec());
+        let f = |input: &[u8]| oracle.encrypt(input);
+        assert_eq!(5, find_prefix_len(&f, BLOCK));
+        assert_eq!(8, find_secret_len(&f, BLOCK, 5));
+    }
+
+    #[test]
+    fn check_find_prefix_len_repeated_blocks() {
+        // whole blocks of zeros in the prefix look like two blocks of filler
+        let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+        let oracle = AppendOracle::new(key, vec![0; 37], SECRET.to_vec());
+        let found = byte_at_a_time(&|input: &[u8]| oracle.encrypt(input));
+        assert_eq!(37, found.prefix_len);
+        assert_eq!(SECRET.to_vec(), found.secret);
+
+        // ... and so do repeated blocks in the secret
+        let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+        let oracle = AppendOracle::new(key, b"xyz".to_vec(), vec![b'Z'; 48]);
+        let found = byte_at_a_time(&|input: &[u8]| oracle.encrypt(input));
+        assert_eq!(3, found.prefix_len);
+        assert_eq!(vec![b'Z'; 48], found.secret);
+    }
+
+    // TEST ProfileOracle, cut_and_paste
+    #[test]
+    fn check_profile_oracle() {
+        let oracle = ProfileOracle::random(&mut StdRng::seed_from_u64(16), true);
+        let pairs = oracle.decrypt(&oracle.encrypt("foo@bar.com&role=admin"));
+        assert_eq!(Some("user"), profile::get(&pairs, "role"));
+        assert_eq!(Some("foo@bar.com&role=admin"), profile::get(&pairs, "email"));
+    }
+
+    #[test]
+    fn check_cut_and_paste() {
+        let mut rng = StdRng::seed_from_u64(17);
+        for escape in [true, false].iter() {
+            let oracle = ProfileOracle::random(&mut rng, *escape);
+            for email in ["foo@bar.com", "a@b.c", "someone.long@example.org"].iter() {
+                let pairs = oracle.decrypt(&cut_and_paste(&|e: &str| oracle.encrypt(e), email));
+                assert_eq!(3, pairs.len());
+                assert_eq!(Some("admin"), profile::get(&pairs, "role"));
+                assert_eq!(Some("10"), profile::get(&pairs, "uid"));
+                assert!(profile::get(&pairs, "email").unwrap().ends_with(email));
+            }
+        }
+    }
+
+    #[test]
+    fn check_cut_and_paste_metacharacters() {
+        // quoting & as %26 makes the email 2 bytes longer than it looks
+        let oracle = ProfileOracle::random(&mut StdRng::seed_from_u64(18), true);
+        for email in ["a&b@c.d", "x=y%z@example.org", "&&&&&&&&"].iter() {
+            let pairs = oracle.decrypt(&cut_and_paste(&|e: &str| oracle.encrypt(e), email));
+            assert_eq!(3, pairs.len());
+            assert_eq!(Some("admin"), profile::get(&pairs, "role"));
+            assert!(profile::get(&pairs, "email").unwrap().ends_with(email));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't use ECB")]
+    fn check_byte_at_a_time_cbc() {
+        let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+        let iv = Storage::new_init_vec(&[0; BLOCK], "ascii");
+        byte_at_a_time(&|input: &[u8]| {
+            let padded = aes::pkcs7_pad(&Storage::new_init_vec(input, "ascii"), BLOCK);
+            aes::cbc_encrypt(&padded, &key, &iv).get_data().clone()
+        });
+    }
+}
@@ -0,0 +1,188 @@
+//! input files of the challenges, bundled into the binary and checked against the SHA-256
+//! manifest in input_files/SHA256SUMS (the format of sha256sum, so `sha256sum -c` checks the
+//! repo copies too)
+
+use sha256::sha256_hex;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Fixture -- input file bundled into the binary
+/// name (&str) - file name in input_files/
+/// contents (&str) - contents at build time
+pub struct Fixture {
+    pub name: &'static str,
+    pub contents: &'static str,
+}
+
+pub const BUNDLED: [Fixture; 6] = [
+    Fixture {
+        name: "english.txt",
+        contents: include_str!("../input_files/english.txt"),
+    },
+    Fixture {
+        name: "set1challenge4.txt",
+        contents: include_str!("../input_files/set1challenge4.txt"),
+    },
+    Fixture {
+        name: "set1challenge6.txt",
+        contents: include_str!("../input_files/set1challenge6.txt"),
+    },
+    Fixture {
+        name: "set1challenge7.txt",
+        contents: include_str!("../input_files/set1challenge7.txt"),
+    },
+    Fixture {
+        name: "set1challenge7_answer.txt",
+        contents: include_str!("../input_files/set1challenge7_answer.txt"),
+    },
+    Fixture {
+        name: "set3challenge17.txt",
+        contents: include_str!("../input_files/set3challenge17.txt"),
+    },
+];
+
+const MANIFEST: &str = include_str!("../input_files/SHA256SUMS");
+
+/// directory read instead of the bundled files (None reads the bundled files)
+static DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Check -- a fixture compared w/ the manifest
+/// name (&str) - file name
+/// source (String) - "bundled" or the path that was read
+/// expected (Option<&str>) - digest in the manifest (None when the file isn't listed)
+/// actual (Option<String>) - digest of the contents (None when the file can't be read)
+pub struct Check {
+    pub name: &'static str,
+    pub source: String,
+    pub expected: Option<&'static str>,
+    pub actual: Option<String>,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        match (self.expected, self.actual.as_ref()) {
+            (Some(e), Some(a)) => e == a,
+            _ => false,
+        }
+    }
+}
+
+/// manifest -- every (digest, name) pair in the manifest
+pub fn manifest() -> Vec<(&'static str, &'static str)> {
+    MANIFEST
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.splitn(2, ' ');
+            let digest = parts.next()?;
+            // sha256sum writes " *" before the name of files read in binary mode
+            let name = parts.next()?.trim_start_matches(&[' ', '*'][..]);
+            Some((digest, name))
+        })
+        .collect()
+}
+
+/// expected_digest -- digest of a file in the manifest
+fn expected_digest(name: &str) -> Option<&'static str> {
+    manifest().into_iter().find(|&(_, n)| n == name).map(|(d, _)| d)
+}
+
+/// bundled -- contents of a fixture as it was built into the binary
+/// Parameters: name (&str) - file name in input_files/
+/// Return: &str - contents
+pub fn bundled(name: &str) -> &'static str {
+    match BUNDLED.iter().find(|f| f.name == name) {
+        Some(f) => f.contents,
+        None => panic!("Error: no bundled input file named {}", name),
+    }
+}
+
+/// set_dir -- read the fixtures from a directory instead of the bundled copies
+/// Parameters: dir (Option<&str>) - directory w/ the input files (None goes back to the
+///             bundled files)
+/// Return: void
+pub fn set_dir(dir: Option<&str>) {
+    *DIR.write().expect("Error: fixture directory poisoned") = dir.map(PathBuf::from);
+}
+
+pub fn get_dir() -> Option<PathBuf> {
+    DIR.read().expect("Error: fixture directory poisoned").clone()
+}
+
+/// read_from -- read and check a fixture from a directory
+fn read_from(dir: &Path, name: &str) -> Result<String, String> {
+    let path = dir.join(name);
+    fs::read_to_string(&path).map_err(|e| format!("Error: Unable to read {} ({})", path.display(), e))
+}
+
+/// read -- contents of a fixture, checked against the manifest
+/// The bundled copy is used unless set_dir() points at a directory.
+/// Parameters: name (&str) - file name in input_files/
+/// Return: String - contents (panics when the file is missing or its digest doesn't match)
+pub fn read(name: &str) -> String {
+    let contents = match get_dir() {
+        Some(dir) => read_from(&dir, name).unwrap_or_else(|e| panic!("{}", e)),
+        None => bundled(name).to_owned(),
+    };
+    match expected_digest(name) {
+        Some(d) if d == sha256_hex(contents.as_bytes()) => contents,
+        Some(_) => panic!("Error: {} does not match its SHA-256 in the manifest", name),
+        None => panic!("Error: {} is not listed in the manifest", name),
+    }
+}
+
+/// verify -- check every bundled fixture (or its copy in the directory) against the manifest
+/// Parameters: void
+/// Return: Vec<Check> - one check per bundled file
+pub fn verify() -> Vec<Check> {
+    let dir = get_dir();
+    BUNDLED
+        .iter()
+        .map(|f| {
+            let (source, contents) = match dir {
+                Some(ref d) => (d.join(f.name).display().to_string(), read_from(d, f.name).ok()),
+                None => ("bundled".to_owned(), Some(f.contents.to_owned())),
+            };
+            Check {
+                name: f.name,
+                source,
+                expected: expected_digest(f.name),
+                actual: contents.map(|c| sha256_hex(c.as_bytes())),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // TEST manifest, verify
+    #[test]
+    fn check_manifest() {
+        let names: Vec<&str> = manifest().iter().map(|&(_, n)| n).collect();
+        assert_eq!(BUNDLED.iter().map(|f| f.name).collect::<Vec<&str>>(), names);
+        assert!(manifest().iter().all(|&(d, _)| d.len() == 64));
+    }
+
+    #[test]
+    fn check_bundled() {
+        // the default directory is never changed by the unit tests
+        assert!(verify().iter().all(|c| c.passed() && c.source == "bundled"));
+        assert_eq!(bundled("set1challenge4.txt"), read("set1challenge4.txt"));
+    }
+
+    #[test]
+    fn check_read_from() {
+        let dir = Path::new("./input_files");
+        assert_eq!(bundled("set1challenge6.txt"), read_from(dir, "set1challenge6.txt").unwrap());
+        assert!(read_from(dir, "missing.txt").unwrap_err().starts_with("Error: Unable to read"));
+    }
+
+    #[test]
+    #[should_panic(expected = "no bundled input file named missing.txt")]
+    fn check_bundled_missing() {
+        bundled("missing.txt");
+    }
+}
@@ -0,0 +1,290 @@
+//! partially known keystream used by the XOR attacks and crib dragging
+
+use storage::Storage;
+
+use std::fmt;
+use std::fs;
+
+/// Keystream -- partially known keystream
+/// bytes (Vec<Option<u8>>) - keystream byte at each position, None when unknown
+pub struct Keystream {
+    bytes: Vec<Option<u8>>,
+}
+
+impl Keystream {
+    /// new -- constructor for a keystream where every byte is unknown
+    /// Parameters: length (usize) - number of bytes in the keystream
+    /// Return: Keystream
+    pub fn new(length: usize) -> Keystream {
+        Keystream {
+            bytes: vec![None; length],
+        }
+    }
+
+    /// new_init -- constructor for a fully known keystream
+    /// Parameters: keystream (&Storage) - keystream (ascii)
+    /// Return: Keystream
+    pub fn new_init(keystream: &Storage) -> Keystream {
+        Keystream {
+            bytes: keystream.get_data().iter().map(|b| Some(*b)).collect(),
+        }
+    }
+
+    /// parse -- build a keystream from hex where ?? marks an unknown byte
+    /// Parameters: str_inp (&str) - hex string (whitespace is ignored)
+    /// Return: Keystream
+    pub fn parse(str_inp: &str) -> Keystream {
+        let chars: Vec<char> = str_inp.chars().filter(|c| !c.is_whitespace()).collect();
+        if !chars.len().is_multiple_of(2) {
+            panic!("Error: keystream hex must have an even number of digits");
+        }
+
+        Keystream {
+            bytes: chars
+                .chunks(2)
+                .map(|c| {
+                    if c[0] == '?' && c[1] == '?' {
+                        None
+                    } else {
+                        Some(
+                            (Storage::char_to_u8(c[0], "hex") << 4)
+                                | Storage::char_to_u8(c[1], "hex"),
+                        )
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// load -- read a keystream saved with save
+    /// Parameters: filename (&str) - file to read
+    /// Return: Keystream
+    pub fn load(filename: &str) -> Keystream {
+        Keystream::parse(&fs::read_to_string(filename).expect("Error: Unable to read file"))
+    }
+
+    /// save -- write the keystream as hex where ?? marks an unknown byte
+    /// Parameters: filename (&str) - file to write
+    /// Return: void
+    pub fn save(&self, filename: &str) {
+        fs::write(filename, format!("{}\n", self)).expect("Error: Unable to write file");
+    }
+
+    /// get_bytes -- helper function to get every keystream position
+    /// Parameters: void
+    /// Return: &Vec<Option<u8>> - keystream byte at each position, None when unknown
+    pub fn get_bytes(&self) -> &Vec<Option<u8>> {
+        &self.bytes
+    }
+
+    /// len -- helper function to get the number of positions in the keystream
+    /// Parameters: void
+    /// Return: usize - length of keystream
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// is_empty -- helper function to check for a keystream w/o any positions
+    /// Parameters: void
+    /// Return: bool
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// known -- number of positions in the keystream that are known
+    /// Parameters: void
+    /// Return: usize - number of known bytes
+    pub fn known(&self) -> usize {
+        self.bytes.iter().filter(|b| b.is_some()).count()
+    }
+
+    /// get -- keystream byte at a position
+    /// Parameters: i (usize) - position
+    /// Return: Option<u8> - keystream byte, None when unknown or out of range
+    pub fn get(&self, i: usize) -> Option<u8> {
+        match self.bytes.get(i) {
+            Some(b) => *b,
+            None => None,
+        }
+    }
+
+    /// clear -- forget keystream bytes (positions past the end are ignored)
+    /// Parameters: offset (usize) - first position
+    ///             length (usize) - number of positions
+    /// Return: void
+    pub fn clear(&mut self, offset: usize, length: usize) {
+        let end = offset.saturating_add(length).min(self.bytes.len());
+        for i in offset..end {
+            self.bytes[i] = None;
+        }
+    }
+
+    /// resize -- grow or shrink the keystream (new positions are unknown)
+    /// Parameters: length (usize) - number of positions
+    /// Return: void
+    pub fn resize(&mut self, length: usize) {
+        self.bytes.resize(length, None);
+    }
+
+    /// set -- set the keystream byte at a position (grows the keystream if needed)
+    /// Parameters: i (usize) - position
+    ///             b (Option<u8>) - keystream byte, None to forget it
+    /// Return: void
+    pub fn set(&mut self, i: usize, b: Option<u8>) {
+        if i >= self.bytes.len() {
+            self.bytes.resize(i + 1, None);
+        }
+        self.bytes[i] = b;
+    }
+
+    /// commit -- set the keystream bytes implied by a plaintext guess
+    /// Parameters: cipher (&Storage) - ciphertext the guess belongs to (ascii)
+    ///             offset (usize) - position of the guess in the ciphertext
+    ///             plaintext (&Storage) - guessed plaintext (ascii)
+    /// Return: void
+    pub fn commit(&mut self, cipher: &Storage, offset: usize, plaintext: &Storage) {
+        if plaintext.is_empty() {
+            return;
+        }
+        if offset + plaintext.len() > cipher.len() {
+            panic!(
+                "Error: guess does not fit in the ciphertext. Offset is {}, guess length is {}, \
+                 ciphertext length is {}",
+                offset,
+                plaintext.len(),
+                cipher.len()
+            );
+        }
+
+        let segment = &cipher.index(offset, offset + plaintext.len()) ^ plaintext;
+        for (i, b) in segment.get_data().iter().enumerate() {
+            self.set(offset + i, Some(*b));
+        }
+    }
+
+    /// decrypt -- decrypt a ciphertext w/ the known part of the keystream
+    /// unknown bytes are shown as _ and non printable bytes as .
+    /// Parameters: cipher (&Storage) - ciphertext (ascii)
+    /// Return: String - partially decrypted plaintext
+    pub fn decrypt(&self, cipher: &Storage) -> String {
+        cipher
+            .get_data()
+            .iter()
+            .enumerate()
+            .map(|(i, c)| match self.get(i) {
+                Some(k) => {
+                    let p = c ^ k;
+                    if is_printable(p) {
+                        p as char
+                    } else {
+                        '.'
+                    }
+                }
+                None => '_',
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Keystream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for b in &self.bytes {
+            match b {
+                Some(b) => write!(f, "{:02x}", b)?,
+                None => write!(f, "??")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// is_printable -- helper function to check for printable ascii or a space
+/// Parameters: b (u8) - byte
+/// Return: bool
+pub fn is_printable(b: u8) -> bool {
+    (0x20..0x7F).contains(&b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xor;
+
+    fn ciphertexts() -> (Storage, Vec<Storage>) {
+        let k: Vec<u8> = (0..40).map(|i| ((i * 89 + 7) % 256) as u8).collect();
+        let k = Storage::new_init_vec(&k, "ascii");
+        let c = ["attack at dawn", "the quick brown fox", "meet me at noon"]
+            .iter()
+            .map(|p| xor::xor_keystream(&Storage::new_init(p, "ascii"), &k))
+            .collect();
+        (k, c)
+    }
+
+    // TEST parse, to_string
+    #[test]
+    fn check_keystream_parse_and_to_string() {
+        let k = Keystream::parse("00ff??1A");
+        assert_eq!(4, k.len());
+        assert_eq!(3, k.known());
+        assert_eq!(Some(0xff), k.get(1));
+        assert_eq!(None, k.get(2));
+        assert_eq!(None, k.get(10));
+        assert_eq!("00ff??1a", k.to_string());
+        assert!(!k.is_empty());
+        assert!(Keystream::new(0).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_keystream_parse() {
+        Keystream::parse("00f");
+    }
+
+    // TEST commit, decrypt
+    #[test]
+    fn check_keystream_commit_and_decrypt() {
+        let (k, c) = ciphertexts();
+        let mut keystream = Keystream::new(0);
+        keystream.commit(&c[0], 7, &Storage::new_init("at", "ascii"));
+
+        assert_eq!(Some(k.get_data()[7]), keystream.get(7));
+        assert_eq!(2, keystream.known());
+        assert_eq!("_______at_____", keystream.decrypt(&c[0]));
+        assert_eq!("_______ck__________", keystream.decrypt(&c[1]));
+        assert_eq!("_______ a______", keystream.decrypt(&c[2]));
+
+        keystream.set(8, None);
+        assert_eq!("_______ _______", keystream.decrypt(&c[2]));
+    }
+
+    // TEST clear
+    #[test]
+    fn check_keystream_clear() {
+        let mut k = Keystream::parse("0102030405");
+        k.clear(1, 2);
+        assert_eq!("01????0405", k.to_string());
+        k.clear(4, usize::MAX);
+        k.clear(99999999999, 1);
+        assert_eq!("01????04??", k.to_string());
+        assert_eq!(5, k.len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_keystream_commit_past_end() {
+        let (_, c) = ciphertexts();
+        let mut keystream = Keystream::new(0);
+        keystream.commit(&c[0], 12, &Storage::new_init("dawn", "ascii"));
+    }
+
+    // TEST save, load
+    #[test]
+    fn check_keystream_save_and_load() {
+        let filename = "./target/check_keystream_save_and_load.txt";
+        fs::create_dir_all("./target").unwrap();
+        let k = Keystream::parse("01??03");
+        k.save(filename);
+        assert_eq!("01??03", Keystream::load(filename).to_string());
+        fs::remove_file(filename).unwrap();
+    }
+}
@@ -0,0 +1,42 @@
+//! cryptopals -- encodings, ciphers, block cipher modes, attacks and analysis from the
+//! cryptopals challenges (https://cryptopals.com)
+//!
+//! storage - raw bytes tagged w/ an encoding (ascii, hex or base64)
+//! convert - streaming conversion between encodings
+//! aes - AES-128 in ECB, CBC and CTR mode, PKCS#7 padding and ECB detection
+//! cbc - bit flipping and padding oracle attacks on AES in CBC mode
+//! ecb - ECB/CBC detection oracle and attacks on AES in ECB mode
+//! profile - k=v&k=v cookies of the challenge 13 user profiles
+//! xor - single-byte, repeating-key and many-time-pad XOR attacks
+//! classical - classical ciphers and their breakers
+//! sha256 - SHA-256, used to check the bundled input files
+//! fixtures - input files bundled into the binary and their SHA-256 manifest
+//! analysis - entropy, index of coincidence and Kasiski statistics
+//! crib - crib dragging on ciphertexts that share a keystream
+//! keystream - partially known keystream used by the XOR attacks and crib dragging
+//! challenge, report, set1, set2, set3 - the challenges themselves and how their results are reported
+//! bench - timing of the challenges against a saved baseline
+
+extern crate rand;
+
+pub mod aes;
+pub mod analysis;
+pub mod bench;
+pub mod cbc;
+pub mod challenge;
+pub mod classical;
+pub mod convert;
+pub mod crib;
+pub mod ecb;
+pub mod fixtures;
+pub mod keystream;
+pub mod profile;
+pub mod report;
+pub mod set1;
+pub mod set2;
+pub mod set3;
+pub mod sha256;
+pub mod storage;
+pub mod xor;
+
+pub use storage::Storage;
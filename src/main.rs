@@ -1,9 +1,17 @@
 extern crate clap;
-use clap::{Arg, App};
+extern crate cryptopals;
+use clap::{Arg, App, ArgMatches, SubCommand};
 
-pub mod set1;
-pub mod storage;
-pub mod challenge;
+use cryptopals::{aes, analysis, bench, challenge, convert, crib, fixtures, report, set1, xor};
+use cryptopals::challenge::Overrides;
+use cryptopals::keystream::Keystream;
+use cryptopals::storage::Storage;
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process;
+use std::time::Duration;
 
 fn main() {
     let matches = App::new("Cryptopals")
@@ -20,27 +28,728 @@ fn main() {
                  .long("challenge")
                  .takes_value(true)
                  .help("Which challenge should run"))
+        .arg(Arg::with_name("fixtures")
+                 .long("fixtures")
+                 .takes_value(true)
+                 .env("CRYPTOPALS_FIXTURES")
+                 .help("Read the challenge input files from this directory instead of the bundled copies"))
+        .args(&override_args())
+        .subcommand(SubCommand::with_name("run")
+                 .about("Run many challenges in sequence and print a summary table")
+                 .arg(Arg::with_name("all")
+                          .long("all")
+                          .conflicts_with_all(&["set", "challenge"])
+                          .help("Run every implemented challenge"))
+                 .arg(Arg::with_name("set")
+                          .short("s")
+                          .long("set")
+                          .takes_value(true)
+                          .help("Only run challenges from this set"))
+                 .arg(Arg::with_name("challenge")
+                          .short("c")
+                          .long("challenge")
+                          .takes_value(true)
+                          .help("Only run this challenge or range of challenges (e.g. 3-7)"))
+                 .arg(Arg::with_name("quiet")
+                          .short("q")
+                          .long("quiet")
+                          .help("Only print the summary table"))
+                 .arg(Arg::with_name("format")
+                          .short("f")
+                          .long("format")
+                          .takes_value(true)
+                          .possible_values(&["text", "json", "tap", "junit"])
+                          .default_value("text")
+                          .help("Output format of the results"))
+                 .arg(Arg::with_name("jobs")
+                          .short("j")
+                          .long("jobs")
+                          .takes_value(true)
+                          .default_value("0")
+                          .help("Number of challenges run at the same time (0 uses every core)"))
+                 .arg(Arg::with_name("timeout")
+                          .long("timeout")
+                          .takes_value(true)
+                          .help("Fail a challenge that runs longer than this many seconds"))
+                 .args(&override_args()))
+        .subcommand(SubCommand::with_name("list")
+                 .about("List every implemented challenge as set-id and title"))
+        .subcommand(SubCommand::with_name("fixtures")
+                 .about("Check the challenge input files against their SHA-256 manifest"))
+        .subcommand(SubCommand::with_name("analyze")
+                 .about("Print entropy, index of coincidence, histogram and Kasiski statistics")
+                 .arg(Arg::with_name("file")
+                          .required(true)
+                          .index(1)
+                          .help("File to analyze"))
+                 .arg(Arg::with_name("type")
+                          .short("t")
+                          .long("type")
+                          .takes_value(true)
+                          .possible_values(&["ascii", "hex", "base64"])
+                          .default_value("ascii")
+                          .help("Encoding of the file (ascii reads raw bytes)")))
+        .subcommand(SubCommand::with_name("crib")
+                 .about("Interactive crib dragging on ciphertexts that share a keystream")
+                 .arg(Arg::with_name("file")
+                          .required(true)
+                          .index(1)
+                          .help("File with one ciphertext per line"))
+                 .arg(Arg::with_name("type")
+                          .short("t")
+                          .long("type")
+                          .takes_value(true)
+                          .possible_values(&["ascii", "hex", "base64"])
+                          .default_value("base64")
+                          .help("Encoding of each line"))
+                 .arg(Arg::with_name("keystream")
+                          .short("k")
+                          .long("keystream")
+                          .takes_value(true)
+                          .default_value("keystream.txt")
+                          .help("Keystream file to resume from and save to (hex, ?? for unknown)")))
+        .subcommand(SubCommand::with_name("convert")
+                 .about("Convert data between ascii, hex and base64")
+                 .arg(Arg::with_name("file")
+                          .index(1)
+                          .default_value("-")
+                          .help("File to convert (- reads stdin)"))
+                 .arg(Arg::with_name("from")
+                          .long("from")
+                          .takes_value(true)
+                          .required(true)
+                          .possible_values(&["ascii", "hex", "base64"])
+                          .help("Encoding of the input (ascii reads raw bytes)"))
+                 .arg(Arg::with_name("to")
+                          .long("to")
+                          .takes_value(true)
+                          .required(true)
+                          .possible_values(&["ascii", "hex", "base64"])
+                          .help("Encoding of the output (ascii writes raw bytes)"))
+                 .arg(Arg::with_name("wrap")
+                          .short("w")
+                          .long("wrap")
+                          .takes_value(true)
+                          .default_value("0")
+                          .help("Characters per line of hex or base64 output (0 writes one line)"))
+                 .arg(Arg::with_name("lenient")
+                          .long("lenient")
+                          .help("Skip characters outside the input alphabet instead of failing")))
+        .subcommand(SubCommand::with_name("detect")
+                 .about("Find every line that was encrypted with single-byte XOR")
+                 .arg(Arg::with_name("file")
+                          .required(true)
+                          .index(1)
+                          .help("File with one ciphertext per line"))
+                 .arg(Arg::with_name("type")
+                          .short("t")
+                          .long("type")
+                          .takes_value(true)
+                          .possible_values(&["hex", "base64"])
+                          .default_value("hex")
+                          .help("Encoding of each line"))
+                 .arg(Arg::with_name("threshold")
+                          .long("threshold")
+                          .takes_value(true)
+                          .allow_hyphen_values(true)
+                          .default_value("-4.0")
+                          .help("Minimum english score per byte (closer to 0 is stricter)"))
+                 .arg(Arg::with_name("threads")
+                          .short("j")
+                          .long("threads")
+                          .takes_value(true)
+                          .default_value("0")
+                          .help("Number of worker threads (0 uses every core)")))
+        .subcommand(SubCommand::with_name("xor")
+                 .about("XOR a file against a repeating key")
+                 .arg(Arg::with_name("file")
+                          .index(1)
+                          .default_value("-")
+                          .help("File to XOR (- reads stdin)"))
+                 .arg(Arg::with_name("key")
+                          .short("k")
+                          .long("key")
+                          .takes_value(true)
+                          .required(true)
+                          .allow_hyphen_values(true)
+                          .help("Repeating key"))
+                 .arg(encoding_arg("key", "ascii"))
+                 .arg(encoding_arg("input", "ascii"))
+                 .arg(encoding_arg("output", "hex")))
+        .subcommand(SubCommand::with_name("aes")
+                 .about("Encrypt or decrypt a file with AES-128 in ECB, CBC or CTR mode")
+                 .arg(Arg::with_name("file")
+                          .index(1)
+                          .default_value("-")
+                          .help("File to encrypt or decrypt (- reads stdin)"))
+                 .arg(Arg::with_name("mode")
+                          .short("m")
+                          .long("mode")
+                          .takes_value(true)
+                          .required(true)
+                          .possible_values(&["ecb", "cbc", "ctr"])
+                          .help("Block cipher mode"))
+                 .arg(Arg::with_name("encrypt")
+                          .short("e")
+                          .long("encrypt")
+                          .conflicts_with("decrypt")
+                          .required_unless("decrypt")
+                          .help("Encrypt the file"))
+                 .arg(Arg::with_name("decrypt")
+                          .short("d")
+                          .long("decrypt")
+                          .help("Decrypt the file"))
+                 .arg(Arg::with_name("key")
+                          .short("k")
+                          .long("key")
+                          .takes_value(true)
+                          .required(true)
+                          .allow_hyphen_values(true)
+                          .help("16 byte key"))
+                 .arg(Arg::with_name("iv")
+                          .long("iv")
+                          .takes_value(true)
+                          .required_if("mode", "cbc")
+                          .help("16 byte iv for cbc, 8 byte little endian nonce for ctr (default 0)"))
+                 .arg(Arg::with_name("no-padding")
+                          .long("no-padding")
+                          .help("Do not add or strip PKCS#7 padding in ecb and cbc"))
+                 .arg(encoding_arg("key", "ascii"))
+                 .arg(encoding_arg("iv", "hex"))
+                 .arg(encoding_arg("input", "ascii"))
+                 .arg(encoding_arg("output", "ascii")))
+        .subcommand(SubCommand::with_name("break")
+                 .about("Run an automated attack on a file and print ranked candidates")
+                 .arg(Arg::with_name("attack")
+                          .required(true)
+                          .index(1)
+                          .possible_values(&["xor-single", "xor-repeat", "ecb-detect", "many-time-pad"])
+                          .help("Attack to run"))
+                 .arg(Arg::with_name("file")
+                          .required(true)
+                          .index(2)
+                          .help("Ciphertext (one per line for ecb-detect and many-time-pad)"))
+                 .arg(Arg::with_name("type")
+                          .short("t")
+                          .long("type")
+                          .takes_value(true)
+                          .possible_values(&["ascii", "hex", "base64"])
+                          .default_value("hex")
+                          .help("Encoding of the file"))
+                 .arg(Arg::with_name("top")
+                          .short("n")
+                          .long("top")
+                          .takes_value(true)
+                          .default_value("5")
+                          .help("Number of candidates to print"))
+                 .arg(Arg::with_name("min")
+                          .long("min")
+                          .takes_value(true)
+                          .default_value("2")
+                          .help("Smallest key size to try (xor-repeat)"))
+                 .arg(Arg::with_name("max")
+                          .long("max")
+                          .takes_value(true)
+                          .default_value("40")
+                          .help("Largest key size to try (xor-repeat)")))
+        .subcommand(SubCommand::with_name("bench")
+                 .about("Time challenges over many runs and compare them w/ a baseline")
+                 .arg(Arg::with_name("set")
+                          .short("s")
+                          .long("set")
+                          .takes_value(true)
+                          .help("Only time challenges from this set"))
+                 .arg(Arg::with_name("challenge")
+                          .short("c")
+                          .long("challenge")
+                          .takes_value(true)
+                          .help("Only time this challenge or range of challenges (e.g. 3-7)"))
+                 .arg(Arg::with_name("runs")
+                          .short("n")
+                          .long("runs")
+                          .takes_value(true)
+                          .default_value("5")
+                          .help("Number of runs per challenge"))
+                 .arg(Arg::with_name("baseline")
+                          .short("b")
+                          .long("baseline")
+                          .takes_value(true)
+                          .help("Baseline JSON to compare against"))
+                 .arg(Arg::with_name("save")
+                          .long("save")
+                          .takes_value(true)
+                          .help("Write the results as a baseline JSON"))
+                 .arg(Arg::with_name("tolerance")
+                          .long("tolerance")
+                          .takes_value(true)
+                          .default_value("20")
+                          .help("Percent slower than the baseline before a challenge fails")))
         .get_matches();
 
+    fixtures::set_dir(matches.value_of("fixtures"));
+
+    if matches.subcommand_matches("fixtures").is_some() {
+        let checks = fixtures::verify();
+        for c in checks.iter() {
+            let status = match (c.expected, c.actual.as_ref()) {
+                (None, _) => "UNLISTED",
+                (_, None) => "MISSING",
+                _ if c.passed() => "OK",
+                _ => "MISMATCH",
+            };
+            println!(
+                "{:<8} {:<64} {}",
+                status,
+                c.actual.as_ref().map_or("-", |a| a.as_str()),
+                c.source
+            );
+        }
+        if !checks.iter().all(|c| c.passed()) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("analyze") {
+        let data = read_file(matches.value_of("file").unwrap(), matches.value_of("type").unwrap());
+        analysis::print_report(&data);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("crib") {
+        let ciphertexts = read_lines(matches.value_of("file").unwrap(), matches.value_of("type").unwrap());
+        let filename = matches.value_of("keystream").unwrap();
+        let keystream = if Path::new(filename).exists() {
+            Keystream::load(filename)
+        } else {
+            Keystream::new(0)
+        };
+        // the session keeps every guess, not just the ones written w/ save
+        let keystream = crib::interactive(&ciphertexts, keystream, filename);
+        keystream.save(filename);
+        println!("Saved keystream to {}", filename);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("convert") {
+        let options = convert::ConvertOptions {
+            wrap: matches
+              .value_of("wrap").unwrap()
+              .parse::<usize>().expect("Please make sure wrap is a number!"),
+            strict: !matches.is_present("lenient"),
+        };
+        let from = matches.value_of("from").unwrap();
+        let to = matches.value_of("to").unwrap();
+        let stdout = io::stdout();
+        let mut out = io::BufWriter::new(stdout.lock());
+        match matches.value_of("file").unwrap() {
+            "-" => convert::convert(io::stdin().lock(), &mut out, from, to, &options),
+            f => convert::convert(fs::File::open(f).expect("Error: Unable to read file"), &mut out, from, to, &options),
+        };
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("detect") {
+        let threshold = matches
+          .value_of("threshold").unwrap()
+          .parse::<f32>().expect("Please make sure threshold is a number!");
+        let threads = matches
+          .value_of("threads").unwrap()
+          .parse::<usize>().expect("Please make sure threads is a number!");
+
+        // empty lines are kept so the reported line numbers match the file
+        let data_type = matches.value_of("type").unwrap();
+        let contents = fs::read_to_string(matches.value_of("file").unwrap()).expect("Error: Unable to read file");
+        let lines: Vec<Storage> = contents
+            .lines()
+            .map(|l| Storage::new_init(l.trim(), data_type))
+            .collect();
+
+        let found = xor::detect_single_byte(&lines, threshold, threads);
+        println!("{} of {} lines above {}", found.len(), lines.len(), threshold);
+        for d in found {
+            println!("line {:>5}  key 0x{:02x}  score {:.3}  {:?}", d.line, d.key, d.score, d.plaintext.to_string());
+        }
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("xor") {
+        let data = read_file(matches.value_of("file").unwrap(), matches.value_of("input-encoding").unwrap());
+        let key = decode_arg(matches.value_of("key").unwrap(), matches.value_of("key-encoding").unwrap());
+        write_output(&xor::xor_repeating_key(&data, &key), matches.value_of("output-encoding").unwrap());
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("aes") {
+        let data = read_file(matches.value_of("file").unwrap(), matches.value_of("input-encoding").unwrap());
+        let key = decode_arg(matches.value_of("key").unwrap(), matches.value_of("key-encoding").unwrap());
+        let iv = matches
+            .value_of("iv")
+            .map(|iv| decode_arg(iv, matches.value_of("iv-encoding").unwrap()));
+        let encrypt = matches.is_present("encrypt");
+        let padding = !matches.is_present("no-padding");
+
+        let out = match aes::Mode::parse(matches.value_of("mode").unwrap()) {
+            aes::Mode::Ctr => {
+                let nonce = match iv {
+                    Some(iv) => {
+                        if iv.len() != 8 {
+                            panic!("Error: the ctr nonce must be 8 bytes, this nonce is {} bytes", iv.len());
+                        }
+                        let mut bytes = [0u8; 8];
+                        bytes.copy_from_slice(iv.get_data());
+                        u64::from_le_bytes(bytes)
+                    }
+                    None => 0,
+                };
+                aes::ctr(&data, &key, nonce)
+            }
+            mode => {
+                let data = if encrypt && padding { aes::pkcs7_pad(&data, aes::BLOCK) } else { data };
+                let out = match (mode, encrypt) {
+                    (aes::Mode::Ecb, true) => aes::ecb_encrypt(&data, &key),
+                    (aes::Mode::Ecb, false) => aes::ecb_decrypt(&data, &key),
+                    (_, true) => aes::cbc_encrypt(&data, &key, iv.as_ref().unwrap()),
+                    (_, false) => aes::cbc_decrypt(&data, &key, iv.as_ref().unwrap()),
+                };
+                if !encrypt && padding { aes::pkcs7_unpad(&out, aes::BLOCK) } else { out }
+            }
+        };
+        write_output(&out, matches.value_of("output-encoding").unwrap());
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("break") {
+        let filename = matches.value_of("file").unwrap();
+        let data_type = matches.value_of("type").unwrap();
+        let top = matches
+          .value_of("top").unwrap()
+          .parse::<usize>().expect("Please make sure top is a number!");
+
+        match matches.value_of("attack").unwrap() {
+            "xor-single" => {
+                let freq = set1::helper::freq::get_char_freq_table();
+                let data = read_file(filename, data_type);
+                exit_if_empty(data.is_empty(), filename);
+                for c in xor::rank_single_byte(&data, &freq).iter().take(top) {
+                    println!(
+                        "key 0x{:02x}  score {:.3}  {:?}",
+                        c.key,
+                        c.score / data.len() as f32,
+                        c.plaintext.to_string()
+                    );
+                }
+            }
+            "xor-repeat" => {
+                let min = matches
+                  .value_of("min").unwrap()
+                  .parse::<usize>().expect("Please make sure min is a number!");
+                let max = matches
+                  .value_of("max").unwrap()
+                  .parse::<usize>().expect("Please make sure max is a number!");
+                let data = read_file(filename, data_type);
+                exit_if_empty(data.is_empty(), filename);
+                let ranked = xor::rank_repeating_key(&data, min, max, top, xor::KeySizeMethod::HammingDistance);
+                for c in ranked {
+                    println!(
+                        "keysize {:>3}  score {:.3}  printable {:.2}  key {:?}",
+                        c.keysize,
+                        c.score,
+                        c.confidence,
+                        c.key.to_string()
+                    );
+                }
+            }
+            "ecb-detect" => {
+                let lines = read_lines(filename, data_type);
+                exit_if_empty(lines.is_empty(), filename);
+                for d in aes::detect_ecb(&lines).iter().take(top) {
+                    println!("line {:>5}  repeated blocks {:>3} of {}", d.line, d.repeats, d.blocks);
+                }
+            }
+            _ => {
+                let lines = read_lines(filename, data_type);
+                exit_if_empty(lines.is_empty(), filename);
+                let freq = set1::helper::freq::get_char_freq_table();
+                let ans = xor::break_many_time_pad(&lines);
+                let mut ranked: Vec<(usize, f32)> = ans
+                    .plaintexts
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (i, xor::english_score(p, &freq) / p.len().max(1) as f32))
+                    .collect();
+                ranked.sort_by(|l, r| r.1.partial_cmp(&l.1).unwrap_or(std::cmp::Ordering::Equal));
+                let mut keystream = Storage::new_init_vec(ans.keystream.get_data(), "ascii");
+                keystream.change_base("hex");
+                println!("keystream {}", keystream);
+                for (i, score) in ranked.into_iter().take(top) {
+                    println!("line {:>5}  score {:.3}  {:?}", i + 1, score, ans.plaintexts[i].to_string());
+                }
+            }
+        }
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("bench") {
+        let set = matches
+          .value_of("set")
+          .map(|s| s.parse::<u32>().expect("Please make sure set is a number!"));
+        let ids = matches.value_of("challenge").map(challenge::parse_range);
+        let runs = matches
+          .value_of("runs").unwrap()
+          .parse::<usize>().expect("Please make sure runs is a number!");
+        let tolerance = matches
+          .value_of("tolerance").unwrap()
+          .parse::<f64>().expect("Please make sure tolerance is a number!") / 100.0;
+
+        let challenges = challenge::select(set, ids);
+        if challenges.is_empty() {
+            println!("No implemented challenge matches (see `list`)");
+            process::exit(2);
+        }
+        let benches: Vec<bench::Bench> = challenges.iter().map(|c| bench::bench(c.as_ref(), runs)).collect();
+        let comparisons = match matches.value_of("baseline") {
+            Some(f) => bench::compare(&benches, &bench::load_baseline(f)),
+            None => Vec::new(),
+        };
+        bench::print_table(&benches, &comparisons, tolerance);
+        if let Some(f) = matches.value_of("save") {
+            bench::save_baseline(f, &benches);
+        }
+        if comparisons.iter().any(|c| c.regressed(tolerance)) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("run") {
+        if !matches.is_present("all") && !matches.is_present("set") && !matches.is_present("challenge") {
+            println!("Please include --all, --set or --challenge.");
+            process::exit(2);
+        }
+        let set = matches
+          .value_of("set")
+          .map(|s| s.parse::<u32>().expect("Please make sure set is a number!"));
+        let ids = matches.value_of("challenge").map(challenge::parse_range);
+
+        let challenges = challenge::select(set, ids);
+        if challenges.is_empty() {
+            println!("No implemented challenge matches (see `list`)");
+            process::exit(2);
+        }
+        // one set of inputs can't stand in for several challenges (and stdin is only read once)
+        if challenges.len() > 1
+            && ["input", "input-type", "input-file", "key"].iter().any(|a| matches.is_present(a))
+        {
+            println!(
+                "--input, --input-type, --input-file and --key need exactly one challenge, {} are selected",
+                challenges.len()
+            );
+            process::exit(2);
+        }
+        let format = report::Format::parse(matches.value_of("format").unwrap());
+        let verbose = format == report::Format::Text && !matches.is_present("quiet");
+        let options = report::RunOptions {
+            jobs: matches
+              .value_of("jobs").unwrap()
+              .parse::<usize>().expect("Please make sure jobs is a number!"),
+            timeout: matches.value_of("timeout").map(|t| {
+                Duration::from_secs_f64(t.parse::<f64>().expect("Please make sure timeout is a number!"))
+            }),
+            verbose,
+        };
+        let reports = report::run_all(challenges, &read_overrides(matches), &options);
+        match format {
+            report::Format::Text => report::print_table(&reports),
+            _ => print!("{}", report::render(&reports, format)),
+        }
+        if !report::all_passed(&reports) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    if matches.subcommand_matches("list").is_some() {
+        for c in challenge::registry() {
+            println!("{:>2}-{:<3} {}", c.set(), c.id(), c.title());
+        }
+        return;
+    }
+
     let set = matches
-      .value_of("set").expect("Please include a challenge number.")
-      .parse::<i32>().expect("Please make sure challenge is a number!");
+      .value_of("set").expect("Please include a set number.")
+      .parse::<u32>().expect("Please make sure set is a number!");
 
-    let challenge = matches
+    let id = matches
       .value_of("challenge").expect("Please include a challenge number.")
-      .parse::<i32>().expect("Please make sure challenge is a number!");
-
-    match set {
-        1 => match challenge {
-            1 => set1::challenge1(),
-            2 => set1::challenge2(),
-            3 => set1::challenge3(),
-            4 => set1::challenge4(),
-            5 => set1::challenge5(),
-            6 => set1::challenge6(),
-            7 => set1::challenge7(),
-            _ => println!("Please include a valid challenge! {}", challenge)
-        },
-        _ => println!("Please include a valid set! {}", set)
+      .parse::<u32>().expect("Please make sure challenge is a number!");
+
+    match challenge::find(set, id) {
+        Some(c) => {
+            let outcome = c.run_with(&read_overrides(&matches));
+            outcome.print();
+            let verdict = outcome.verify();
+            verdict.print();
+            if !verdict.passed() {
+                process::exit(1);
+            }
+        }
+        None => {
+            println!("Please include a valid challenge! Set {}, Challenge {} (see `list`)", set, id);
+            process::exit(2);
+        }
+    }
+}
+
+/// override_args -- command line flags that replace the canned inputs of a challenge
+/// Parameters: void
+/// Return: Vec<Arg> - --input, --input-type, --input-file and --key
+fn override_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("input")
+            .long("input")
+            .takes_value(true)
+            .allow_hyphen_values(true)
+            .conflicts_with("input-file")
+            .help("Replace the challenge input (- reads stdin)"),
+        Arg::with_name("input-type")
+            .long("input-type")
+            .takes_value(true)
+            .possible_values(&["ascii", "hex", "base64"])
+            .help("Encoding of the replaced input"),
+        Arg::with_name("input-file")
+            .long("input-file")
+            .takes_value(true)
+            .allow_hyphen_values(true)
+            .help("Replace the challenge input file (- reads stdin)"),
+        Arg::with_name("key")
+            .long("key")
+            .takes_value(true)
+            .allow_hyphen_values(true)
+            .help("Replace the challenge key"),
+    ]
+}
+
+/// read_overrides -- collect the flags from override_args
+/// Parameters: matches (&ArgMatches) - parsed command line
+/// Return: Overrides - inputs that replace the canned ones
+fn read_overrides(matches: &ArgMatches) -> Overrides {
+    Overrides {
+        input: matches.value_of("input").map(|i| match i {
+            "-" => challenge::read_source("-").trim_end().to_owned(),
+            i => i.to_owned(),
+        }),
+        input_type: matches.value_of("input-type").map(|t| t.to_owned()),
+        input_file: matches.value_of("input-file").map(|f| f.to_owned()),
+        key: matches.value_of("key").map(|k| k.to_owned()),
+    }
+}
+
+/// encoding_arg -- --NAME-encoding flag for a value that may be ascii, hex or base64
+/// Parameters: name (&'static str) - value the encoding applies to (key, iv, input or output)
+///             default (&'static str) - default encoding
+/// Return: Arg - the encoding flag
+fn encoding_arg<'a, 'b>(name: &'static str, default: &'static str) -> Arg<'a, 'b> {
+    let (long, help): (&'static str, &'static str) = match name {
+        "key" => ("key-encoding", "Encoding of the key"),
+        "iv" => ("iv-encoding", "Encoding of the iv"),
+        "input" => ("input-encoding", "Encoding of the input (ascii reads raw bytes)"),
+        _ => ("output-encoding", "Encoding of the output (ascii writes raw bytes)"),
+    };
+    Arg::with_name(long)
+        .long(long)
+        .takes_value(true)
+        .possible_values(&["ascii", "hex", "base64"])
+        .default_value(default)
+        .help(help)
+}
+
+/// decode_arg -- decode a command line value into raw bytes
+/// Parameters: value (&str) - value as typed
+///             data_type (&str) - encoding of the value (ascii, hex or base64)
+/// Return: Storage - raw bytes (ascii)
+fn decode_arg(value: &str, data_type: &str) -> Storage {
+    let mut data = Storage::new_init(value, data_type);
+    data.change_base("ascii");
+    data
+}
+
+/// write_output -- write raw bytes to stdout in an encoding
+/// ascii writes the bytes as they are, hex and base64 end w/ a newline
+/// Parameters: data (&Storage) - raw bytes (ascii)
+///             data_type (&str) - output encoding (ascii, hex or base64)
+/// Return: void
+fn write_output(data: &Storage, data_type: &str) {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    if data_type == "ascii" {
+        out.write_all(data.get_data()).expect("Error: Unable to write output");
+    } else if !data.is_empty() {
+        let mut data = Storage::new_init_vec(data.get_data(), "ascii");
+        data.change_base(data_type);
+        writeln!(out, "{}", data).expect("Error: Unable to write output");
+    }
+    out.flush().expect("Error: Unable to write output");
+}
+
+/// read_file -- read a file into a storage of raw bytes
+/// hex and base64 files are decoded (whitespace is ignored)
+/// Parameters: filename (&str) - file to read (- reads stdin)
+///             data_type (&str) - encoding of the file (ascii, hex or base64)
+/// Return: Storage - contents of the file (ascii)
+fn read_file(filename: &str, data_type: &str) -> Storage {
+    let contents = match filename {
+        "-" => {
+            let mut contents: Vec<u8> = Vec::new();
+            io::stdin().read_to_end(&mut contents).expect("Error: Unable to read stdin");
+            contents
+        }
+        f => fs::read(f).expect("Error: Unable to read file"),
+    };
+    if data_type == "ascii" {
+        return Storage::new_init_vec(&contents, "ascii");
+    }
+
+    let contents = String::from_utf8(contents).expect("Error: hex and base64 input must be text");
+    let contents: String = contents.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut data = Storage::new_init(&contents, data_type);
+    data.change_base("ascii");
+    data
+}
+
+/// read_lines -- read a file where every non empty line is a separate message
+/// Parameters: filename (&str) - file to read (- reads stdin)
+///             data_type (&str) - encoding of each line (ascii, hex or base64)
+/// Return: Vec<Storage> - one storage per line (ascii)
+fn read_lines(filename: &str, data_type: &str) -> Vec<Storage> {
+    let contents = match filename {
+        "-" => {
+            let mut contents = String::new();
+            io::stdin().read_to_string(&mut contents).expect("Error: Unable to read stdin");
+            contents
+        }
+        f => fs::read_to_string(f).expect("Error: Unable to read file"),
+    };
+    contents
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            let mut line = Storage::new_init(if data_type == "ascii" { l } else { l.trim() }, data_type);
+            line.change_base("ascii");
+            line
+        })
+        .collect()
+}
+
+/// exit_if_empty -- print a usage error and exit when an attack has nothing to work on
+/// Parameters: empty (bool) - true when the input has no ciphertext
+///             filename (&str) - file the input was read from
+/// Return: void
+fn exit_if_empty(empty: bool, filename: &str) {
+    if empty {
+        let name = if filename == "-" { "stdin" } else { filename };
+        println!("No ciphertext in {}, please give break a non-empty file", name);
+        process::exit(2);
     }
 }
@@ -0,0 +1,147 @@
+//! k=v&k=v cookies as used by the user profiles of challenge 13
+//! encode() quotes the metacharacters & and = (and % itself) as %26, %3D and %25 so a value
+//! can't add pairs, encode_unescaped() is the vulnerable version that copies values as they are
+
+/// escape -- quote the metacharacters of a key or value
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '%' => out.push_str("%25"),
+            '&' => out.push_str("%26"),
+            '=' => out.push_str("%3D"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// unescape -- undo escape (anything that isn't a valid %XX is kept as it is)
+fn unescape(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out: Vec<u8> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let digit = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+        let quoted = match bytes.get(i..i + 3) {
+            Some(&[b'%', hi, lo]) => digit(hi).and_then(|h| digit(lo).map(|l| h * 16 + l)),
+            _ => None,
+        };
+        match quoted {
+            Some(b) => {
+                out.push(b);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// encode -- join pairs into a cookie, quoting & = and % in keys and values
+/// Parameters: pairs (&[(&str, &str)]) - keys and values in order
+/// Return: String - k=v&k=v
+pub fn encode(pairs: &[(&str, &str)]) -> String {
+    pairs
+        .iter()
+        .map(|&(k, v)| format!("{}={}", escape(k), escape(v)))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+/// encode_unescaped -- join pairs into a cookie w/o quoting anything (vulnerable, a value w/
+/// & or = adds pairs)
+/// Parameters: pairs (&[(&str, &str)]) - keys and values in order
+/// Return: String - k=v&k=v
+pub fn encode_unescaped(pairs: &[(&str, &str)]) -> String {
+    pairs
+        .iter()
+        .map(|&(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+/// decode -- split a cookie into pairs and unquote them.  A pair w/o = gets an empty value.
+/// Parameters: cookie (&str) - k=v&k=v
+/// Return: Vec<(String, String)> - keys and values in order (duplicates are kept)
+pub fn decode(cookie: &str) -> Vec<(String, String)> {
+    cookie
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let mut kv = p.splitn(2, '=');
+            let k = kv.next().unwrap_or("");
+            let v = kv.next().unwrap_or("");
+            (unescape(k), unescape(v))
+        })
+        .collect()
+}
+
+/// get -- value of the first pair w/ the key
+/// Parameters: pairs (&[(String, String)]) - decoded cookie
+///             key (&str) - key to look up
+/// Return: Option<&str> - value
+pub fn get<'a>(pairs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+}
+
+/// profile_for -- cookie of a new user (email, uid 10 and role user)
+/// Parameters: email (&str) - email address of the user
+/// Return: String - email=...&uid=10&role=user
+pub fn profile_for(email: &str) -> String {
+    encode(&[("email", email), ("uid", "10"), ("role", "user")])
+}
+
+/// profile_for_unescaped -- profile_for w/ the vulnerable encoder
+pub fn profile_for_unescaped(email: &str) -> String {
+    encode_unescaped(&[("email", email), ("uid", "10"), ("role", "user")])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // TEST encode, decode
+    #[test]
+    fn check_encode_decode() {
+        let cookie = encode(&[("foo", "bar"), ("baz", "qux"), ("zap", "zazzle")]);
+        assert_eq!("foo=bar&baz=qux&zap=zazzle", cookie);
+        let pairs = decode(&cookie);
+        assert_eq!(3, pairs.len());
+        assert_eq!(Some("zazzle"), get(&pairs, "zap"));
+        assert_eq!(None, get(&pairs, "role"));
+    }
+
+    #[test]
+    fn check_escape() {
+        let cookie = encode(&[("a=b", "c&d%e")]);
+        assert_eq!("a%3Db=c%26d%25e", cookie);
+        assert_eq!(vec![("a=b".to_owned(), "c&d%e".to_owned())], decode(&cookie));
+        // a lone % is kept
+        assert_eq!(vec![("k".to_owned(), "50%".to_owned())], decode("k=50%"));
+        assert_eq!(vec![("k".to_owned(), "".to_owned())], decode("k"));
+    }
+
+    // TEST profile_for, profile_for_unescaped
+    #[test]
+    fn check_profile_for() {
+        assert_eq!("email=foo@bar.com&uid=10&role=user", profile_for("foo@bar.com"));
+        assert_eq!(profile_for("foo@bar.com"), profile_for_unescaped("foo@bar.com"));
+    }
+
+    #[test]
+    fn check_naive_injection() {
+        let email = "foo@bar.com&role=admin";
+        let vulnerable = decode(&profile_for_unescaped(email));
+        assert_eq!(Some("admin"), get(&vulnerable, "role"));
+        assert_eq!(Some("foo@bar.com"), get(&vulnerable, "email"));
+
+        let escaped = decode(&profile_for(email));
+        assert_eq!(3, escaped.len());
+        assert_eq!(Some("user"), get(&escaped, "role"));
+        assert_eq!(Some(email), get(&escaped, "email"));
+    }
+}
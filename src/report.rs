@@ -0,0 +1,634 @@
+//! run challenges and summarise their results
+
+use challenge::{Challenge, FieldDiff, Outcome, Overrides, Verdict};
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Format -- how the results of a run are written to stdout
+/// Text - outcome of every challenge followed by a summary table
+/// Json - one JSON document w/ every field and verdict
+/// Tap - Test Anything Protocol (version 13) w/ YAML diagnostics for failures
+/// Junit - JUnit XML w/ one testcase per challenge
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Tap,
+    Junit,
+}
+
+impl Format {
+    /// parse -- format from its command line name (text, json, tap or junit)
+    pub fn parse(name: &str) -> Format {
+        match name {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "tap" => Format::Tap,
+            "junit" => Format::Junit,
+            _ => panic!("Error: unknown format {}", name),
+        }
+    }
+}
+
+/// Report -- result of running a single challenge
+/// set (u32) - set the challenge belongs to
+/// id (u32) - challenge number
+/// title (&str) - challenge title
+/// outcome (Option<Outcome>) - expected and actual results, None when the run panicked or
+///                             timed out
+/// verdict (Verdict) - comparison of the expected and actual results (a run that did not
+///                     finish fails the "run" field)
+/// elapsed (Duration) - time spent in run()
+pub struct Report {
+    pub set: u32,
+    pub id: u32,
+    pub title: &'static str,
+    pub outcome: Option<Outcome>,
+    pub verdict: Verdict,
+    pub elapsed: Duration,
+}
+
+impl Report {
+    /// header -- line printed above the outcome of the challenge
+    pub fn header(&self) -> String {
+        match self.outcome.as_ref() {
+            Some(o) => o.get_header().to_owned(),
+            None => format!("Set {}, Challenge {} - {}", self.set, self.id, self.title),
+        }
+    }
+
+    /// print -- outcome (or why there is none) followed by the verdict
+    pub fn print(&self) {
+        match self.outcome.as_ref() {
+            Some(o) => o.print(),
+            None => println!("{}", self.header()),
+        }
+        self.verdict.print();
+    }
+}
+
+/// RunOptions -- how run_all schedules the challenges
+/// jobs (usize) - number of challenges run at the same time (0 uses every core)
+/// timeout (Option<Duration>) - a challenge that runs longer fails and is abandoned
+/// verbose (bool) - print every outcome and verdict (in challenge order)
+#[derive(Clone, Copy)]
+pub struct RunOptions {
+    pub jobs: usize,
+    pub timeout: Option<Duration>,
+    pub verbose: bool,
+}
+
+impl RunOptions {
+    /// sequential -- one challenge at a time w/o a timeout
+    pub fn sequential(verbose: bool) -> RunOptions {
+        RunOptions {
+            jobs: 1,
+            timeout: None,
+            verbose,
+        }
+    }
+}
+
+/// panic_message -- text of a panic payload
+fn panic_message(payload: &Box<dyn Any + Send>) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(s) => s.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(s) => s.clone(),
+            None => "unknown panic".to_owned(),
+        },
+    }
+}
+
+/// unfinished -- report for a run that panicked or timed out
+fn unfinished(c: &dyn Challenge, reason: String, elapsed: Duration) -> Report {
+    Report {
+        set: c.set(),
+        id: c.id(),
+        title: c.title(),
+        outcome: None,
+        verdict: Verdict {
+            checked: vec!["run"],
+            diffs: vec![FieldDiff {
+                field: "run",
+                expected: "completed".to_owned(),
+                actual: Some(reason),
+            }],
+        },
+        elapsed,
+    }
+}
+
+/// run_one -- run and verify a single challenge on its own thread
+/// A panic is caught and reported as a failure.  When the timeout passes the thread is
+/// abandoned (it can't be killed) and the challenge fails.
+/// Parameters: c (Arc<dyn Challenge>) - challenge to run
+///             overrides (&Overrides) - inputs that replace the canned ones
+///             timeout (Option<Duration>) - longest the challenge may run
+/// Return: Report - outcome, verdict and time of the run
+pub fn run_one(c: Arc<dyn Challenge>, overrides: &Overrides, timeout: Option<Duration>) -> Report {
+    let (tx, rx) = mpsc::channel();
+    let runner = c.clone();
+    let overrides = overrides.clone();
+    let start = Instant::now();
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| runner.run_with(&overrides)));
+        let elapsed = start.elapsed();
+        // the receiver is gone when the run timed out
+        let _ = tx.send((result, elapsed));
+    });
+
+    let received = match timeout {
+        Some(t) => rx.recv_timeout(t).map_err(|e| e == RecvTimeoutError::Timeout),
+        None => rx.recv().map_err(|_| false),
+    };
+    match received {
+        Ok((Ok(outcome), elapsed)) => Report {
+            set: c.set(),
+            id: c.id(),
+            title: c.title(),
+            verdict: outcome.verify(),
+            outcome: Some(outcome),
+            elapsed,
+        },
+        Ok((Err(payload), elapsed)) => unfinished(c.as_ref(), format!("panicked: {}", panic_message(&payload)), elapsed),
+        Err(true) => unfinished(
+            c.as_ref(),
+            format!("timed out after {:.3} s", timeout.unwrap().as_secs_f64()),
+            start.elapsed(),
+        ),
+        Err(false) => unfinished(c.as_ref(), "thread exited w/o a result".to_owned(), start.elapsed()),
+    }
+}
+
+/// Queue -- challenges that haven't started, w/ their position in the report
+type Queue = VecDeque<(usize, Arc<dyn Challenge>)>;
+
+/// run_all -- run and verify challenges on a pool of worker threads
+/// Every worker takes the next challenge that hasn't started.  Reports are returned (and
+/// printed when verbose) in the order of the challenges whatever order they finish in.
+/// Parameters: challenges (Vec<Box<dyn Challenge>>) - challenges to run
+///             overrides (&Overrides) - inputs that replace the canned ones
+///             options (&RunOptions) - number of workers, timeout and verbosity
+/// Return: Vec<Report> - one report per challenge
+pub fn run_all(challenges: Vec<Box<dyn Challenge>>, overrides: &Overrides, options: &RunOptions) -> Vec<Report> {
+    let total = challenges.len();
+    let jobs = match options.jobs {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .min(total.max(1));
+
+    let queue: Arc<Mutex<Queue>> = Arc::new(Mutex::new(
+        challenges
+            .into_iter()
+            .map(Arc::from)
+            .enumerate()
+            .collect(),
+    ));
+    let (tx, rx) = mpsc::channel::<(usize, Report)>();
+    for _ in 0..jobs {
+        let queue = queue.clone();
+        let tx = tx.clone();
+        let overrides = overrides.clone();
+        let timeout = options.timeout;
+        thread::spawn(move || loop {
+            let next = queue.lock().expect("Error: challenge queue poisoned").pop_front();
+            match next {
+                Some((i, c)) => {
+                    if tx.send((i, run_one(c, &overrides, timeout))).is_err() {
+                        return;
+                    }
+                }
+                None => return,
+            }
+        });
+    }
+    drop(tx);
+
+    // reports arrive as they finish, print them in challenge order
+    let mut reports: Vec<Option<Report>> = (0..total).map(|_| None).collect();
+    let mut printed = 0;
+    for (i, report) in rx.iter().take(total) {
+        reports[i] = Some(report);
+        while printed < total && reports[printed].is_some() {
+            if options.verbose {
+                reports[printed].as_ref().unwrap().print();
+                println!();
+            }
+            printed += 1;
+        }
+    }
+    reports
+        .into_iter()
+        .map(|r| r.expect("Error: a worker thread exited w/o a report"))
+        .collect()
+}
+
+/// all_passed -- true when every report passed verification
+pub fn all_passed(reports: &[Report]) -> bool {
+    reports.iter().all(|r| r.verdict.passed())
+}
+
+/// print_table -- print one row per report and a totals line
+/// Parameters: reports (&[Report]) - reports to print
+/// Return: void
+pub fn print_table(reports: &Vec<Report>) {
+    let width = reports.iter().map(|r| r.title.len()).max().unwrap_or(0).max(5);
+    println!("{:<9} {:<width$} {:<6} {:>10}  Key results", "Challenge", "Title", "Result", "Time (ms)", width = width);
+    for r in reports {
+        println!(
+            "{:<9} {:<width$} {:<6} {:>10.3}  {}",
+            format!("{}-{}", r.set, r.id),
+            r.title,
+            if r.verdict.passed() { "PASS" } else { "FAIL" },
+            r.elapsed.as_secs_f64() * 1000.0,
+            r.outcome.as_ref().map_or_else(|| r.verdict.diffs[0].actual.clone().unwrap_or_default(), |o| o.summary()),
+            width = width
+        );
+    }
+
+    let passed = reports.iter().filter(|r| r.verdict.passed()).count();
+    let total: Duration = reports.iter().map(|r| r.elapsed).sum();
+    println!(
+        "{} passed, {} failed in {:.3} ms",
+        passed,
+        reports.len() - passed,
+        total.as_secs_f64() * 1000.0
+    );
+}
+
+/// render -- write the reports in a machine readable format
+/// Parameters: reports (&[Report]) - reports to write
+///             format (Format) - Json, Tap or Junit (Text is printed w/ print_table)
+/// Return: String - the whole document
+pub fn render(reports: &[Report], format: Format) -> String {
+    match format {
+        Format::Text => panic!("Error: text reports are printed w/ print_table"),
+        Format::Json => to_json(reports),
+        Format::Tap => to_tap(reports),
+        Format::Junit => to_junit(reports),
+    }
+}
+
+/// json_string -- quote and escape a string for JSON
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// xml_escape -- escape a string for XML text and attributes
+/// Control characters other than whitespace are not allowed in XML 1.0 and are replaced.
+fn xml_escape(s: &str) -> String {
+    let mut out = String::new();
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' | '\r' | '\t' => out.push(c),
+            c if (c as u32) < 0x20 => out.push('\u{fffd}'),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// to_json -- every report as a JSON document
+/// Parameters: reports (&[Report]) - reports to write
+/// Return: String - {"passed", "failed", "elapsed_ms", "challenges": [...]}
+pub fn to_json(reports: &[Report]) -> String {
+    let challenges: Vec<String> = reports
+        .iter()
+        .map(|r| {
+            let fields: Vec<String> = r
+                .outcome
+                .as_ref()
+                .map_or(Vec::new(), |o| o.fields())
+                .iter()
+                .map(|(k, v)| format!("{}: {}", json_string(k), json_string(v)))
+                .collect();
+            let checked: Vec<String> = r.verdict.checked.iter().map(|c| json_string(c)).collect();
+            let diffs: Vec<String> = r
+                .verdict
+                .diffs
+                .iter()
+                .map(|d| {
+                    format!(
+                        "{{\"field\": {}, \"expected\": {}, \"actual\": {}, \"first_difference\": {}}}",
+                        json_string(d.field),
+                        json_string(&d.expected),
+                        d.actual.as_ref().map_or("null".to_owned(), |a| json_string(a)),
+                        d.first_difference().map_or("null".to_owned(), |i| i.to_string())
+                    )
+                })
+                .collect();
+            format!(
+                "    {{\"set\": {}, \"id\": {}, \"title\": {}, \"header\": {}, \"passed\": {}, \"elapsed_ms\": {:.3}, \
+                 \"fields\": {{{}}}, \"checked\": [{}], \"diffs\": [{}]}}",
+                r.set,
+                r.id,
+                json_string(r.title),
+                json_string(&r.header()),
+                r.verdict.passed(),
+                millis(r.elapsed),
+                fields.join(", "),
+                checked.join(", "),
+                diffs.join(", ")
+            )
+        })
+        .collect();
+
+    let passed = reports.iter().filter(|r| r.verdict.passed()).count();
+    let total: Duration = reports.iter().map(|r| r.elapsed).sum();
+    format!(
+        "{{\n  \"passed\": {},\n  \"failed\": {},\n  \"elapsed_ms\": {:.3},\n  \"challenges\": [\n{}\n  ]\n}}\n",
+        passed,
+        reports.len() - passed,
+        millis(total),
+        challenges.join(",\n")
+    )
+}
+
+/// to_tap -- every report as a TAP version 13 test
+/// Parameters: reports (&[Report]) - reports to write
+/// Return: String - TAP stream w/ a YAML block of diffs for every failure
+pub fn to_tap(reports: &[Report]) -> String {
+    let mut out = format!("TAP version 13\n1..{}\n", reports.len());
+    for (i, r) in reports.iter().enumerate() {
+        let status = if r.verdict.passed() { "ok" } else { "not ok" };
+        out.push_str(&format!("{} {} - {}-{} {}\n", status, i + 1, r.set, r.id, r.title));
+        out.push_str("  ---\n");
+        out.push_str(&format!("  elapsed_ms: {:.3}\n", millis(r.elapsed)));
+        for d in &r.verdict.diffs {
+            out.push_str(&format!("  {}:\n", d.field.replace(' ', "_")));
+            out.push_str(&format!("    expected: {}\n", json_string(&d.expected)));
+            out.push_str(&format!(
+                "    actual: {}\n",
+                d.actual.as_ref().map_or("null".to_owned(), |a| json_string(a))
+            ));
+        }
+        out.push_str("  ...\n");
+    }
+    out
+}
+
+/// to_junit -- every report as a JUnit XML testcase
+/// Parameters: reports (&[Report]) - reports to write
+/// Return: String - <testsuites> document w/ one <testsuite> per set
+pub fn to_junit(reports: &[Report]) -> String {
+    let mut sets: Vec<u32> = reports.iter().map(|r| r.set).collect();
+    sets.sort();
+    sets.dedup();
+
+    let total: Duration = reports.iter().map(|r| r.elapsed).sum();
+    let failed = reports.iter().filter(|r| !r.verdict.passed()).count();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"cryptopals\" tests=\"{}\" failures=\"{}\" time=\"{:.6}\">\n",
+        reports.len(),
+        failed,
+        total.as_secs_f64()
+    ));
+    for set in sets {
+        let suite: Vec<&Report> = reports.iter().filter(|r| r.set == set).collect();
+        let time: Duration = suite.iter().map(|r| r.elapsed).sum();
+        out.push_str(&format!(
+            "  <testsuite name=\"set{}\" tests=\"{}\" failures=\"{}\" time=\"{:.6}\">\n",
+            set,
+            suite.len(),
+            suite.iter().filter(|r| !r.verdict.passed()).count(),
+            time.as_secs_f64()
+        ));
+        for r in suite {
+            out.push_str(&format!(
+                "    <testcase classname=\"cryptopals.set{}\" name=\"{}-{} {}\" time=\"{:.6}\"",
+                r.set,
+                r.set,
+                r.id,
+                xml_escape(r.title),
+                r.elapsed.as_secs_f64()
+            ));
+            if r.verdict.passed() {
+                out.push_str("/>\n");
+                continue;
+            }
+            let fields: Vec<&str> = r.verdict.diffs.iter().map(|d| d.field).collect();
+            out.push_str(">\n");
+            out.push_str(&format!(
+                "      <failure message=\"{} differ\">",
+                xml_escape(&fields.join(", "))
+            ));
+            for d in &r.verdict.diffs {
+                out.push_str(&xml_escape(&format!(
+                    "{}: expected {:?}, actual {:?}\n",
+                    d.field, d.expected, d.actual
+                )));
+            }
+            out.push_str("</failure>\n    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use challenge;
+    use challenge::Builder;
+    use storage::Storage;
+
+    // TEST run_one, run_all, all_passed
+    #[test]
+    fn check_run_all() {
+        let challenges = challenge::select(Some(1), Some((1, 3)));
+        let reports = run_all(challenges, &Overrides::new(), &RunOptions::sequential(false));
+        assert_eq!(3, reports.len());
+        assert_eq!(vec![1, 2, 3], reports.iter().map(|r| r.id).collect::<Vec<u32>>());
+        assert_eq!("Single-byte XOR cipher", reports[2].title);
+        assert!(all_passed(&reports));
+    }
+
+    /// Misbehaving -- challenge that panics or sleeps before passing
+    struct Misbehaving {
+        id: u32,
+        panics: bool,
+        sleep: Duration,
+    }
+
+    impl Challenge for Misbehaving {
+        fn id(&self) -> u32 {
+            self.id
+        }
+        fn set(&self) -> u32 {
+            9
+        }
+        fn title(&self) -> &'static str {
+            "misbehaving"
+        }
+        fn run_with(&self, _overrides: &Overrides) -> Outcome {
+            thread::sleep(self.sleep);
+            if self.panics {
+                panic!("Error: challenge {} broke", self.id);
+            }
+            let mut outcome = Builder::new()
+                .header(&self.header())
+                .input(Storage::new_init("x", "ascii"))
+                .expect("answer", "x")
+                .build();
+            outcome.set_actual("answer", "x");
+            outcome
+        }
+    }
+
+    fn misbehaving(id: u32, panics: bool, sleep_ms: u64) -> Box<dyn Challenge> {
+        Box::new(Misbehaving {
+            id,
+            panics,
+            sleep: Duration::from_millis(sleep_ms),
+        })
+    }
+
+    #[test]
+    fn check_run_all_parallel_order() {
+        // later challenges finish first, the reports stay in challenge order
+        let challenges = vec![misbehaving(1, false, 60), misbehaving(2, false, 30), misbehaving(3, false, 0)];
+        let options = RunOptions {
+            jobs: 3,
+            timeout: None,
+            verbose: false,
+        };
+        let reports = run_all(challenges, &Overrides::new(), &options);
+        assert_eq!(vec![1, 2, 3], reports.iter().map(|r| r.id).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn check_run_all_isolation() {
+        let challenges = vec![misbehaving(1, true, 0), misbehaving(2, false, 0), misbehaving(3, false, 2000)];
+        let options = RunOptions {
+            jobs: 2,
+            timeout: Some(Duration::from_millis(200)),
+            verbose: false,
+        };
+        let reports = run_all(challenges, &Overrides::new(), &options);
+        assert_eq!(3, reports.len());
+        assert!(!all_passed(&reports));
+
+        assert!(reports[0].outcome.is_none());
+        assert_eq!(vec!["run"], reports[0].verdict.checked);
+        assert_eq!(
+            Some("panicked: Error: challenge 1 broke".to_owned()),
+            reports[0].verdict.diffs[0].actual
+        );
+        assert_eq!("Set 9, Challenge 1 - misbehaving", reports[0].header());
+
+        assert!(reports[1].verdict.passed());
+
+        assert!(reports[2].outcome.is_none());
+        assert_eq!(Some("timed out after 0.200 s".to_owned()), reports[2].verdict.diffs[0].actual);
+    }
+
+    fn failing_report() -> Report {
+        let mut outcome = Builder::new()
+            .header("Set 9, Challenge 99 - <broken> & \"quoted\"")
+            .input(Storage::new_init("in\tput", "ascii"))
+            .expect("answer", "a\"b")
+            .expect("key", "K")
+            .build();
+        outcome.set_actual("answer", "a\nb");
+        Report {
+            set: 9,
+            id: 99,
+            title: "<broken> & \"quoted\"",
+            verdict: outcome.verify(),
+            outcome: Some(outcome),
+            elapsed: Duration::from_millis(5),
+        }
+    }
+
+    // TEST Format, render
+    #[test]
+    fn check_format_parse() {
+        assert_eq!(Format::Json, Format::parse("json"));
+        assert_eq!(Format::Junit, Format::parse("junit"));
+    }
+
+    #[test]
+    fn check_to_json() {
+        let json = render(&[failing_report()], Format::Json);
+        assert!(json.contains("\"passed\": 0,"));
+        assert!(json.contains("\"failed\": 1,"));
+        assert!(json.contains("\"title\": \"<broken> & \\\"quoted\\\"\""));
+        assert!(json.contains("\"input\": \"in\\tput\""));
+        assert!(json.contains("\"checked\": [\"answer\", \"key\"]"));
+        assert!(json.contains(
+            "{\"field\": \"answer\", \"expected\": \"a\\\"b\", \"actual\": \"a\\nb\", \"first_difference\": 1}"
+        ));
+        assert!(json.contains("\"actual\": null, \"first_difference\": null"));
+    }
+
+    #[test]
+    fn check_to_tap() {
+        let reports = run_all(
+            challenge::select(Some(1), Some((1, 1))),
+            &Overrides::new(),
+            &RunOptions::sequential(false),
+        );
+        let tap = render(&reports, Format::Tap);
+        assert!(tap.starts_with("TAP version 13\n1..1\nok 1 - 1-1 hex to base64\n"));
+
+        let tap = to_tap(&[failing_report()]);
+        assert!(tap.contains("not ok 1 - 9-99 <broken>"));
+        assert!(tap.contains("  answer:\n    expected: \"a\\\"b\"\n    actual: \"a\\nb\"\n"));
+        assert!(tap.contains("  key:\n    expected: \"K\"\n    actual: null\n"));
+    }
+
+    #[test]
+    fn check_to_junit() {
+        let xml = to_junit(&[failing_report()]);
+        assert!(xml.contains("<testsuites name=\"cryptopals\" tests=\"1\" failures=\"1\""));
+        assert!(xml.contains("<testsuite name=\"set9\" tests=\"1\" failures=\"1\" time=\"0.005000\">"));
+        assert!(xml.contains("name=\"9-99 &lt;broken&gt; &amp; &quot;quoted&quot;\""));
+        assert!(xml.contains("<failure message=\"answer, key differ\">"));
+        assert!(xml.ends_with("</testsuites>\n"));
+    }
+
+    #[test]
+    fn check_to_junit_one_suite_per_set() {
+        let report = |set: u32, id: u32| Report {
+            set,
+            id,
+            ..failing_report()
+        };
+        let xml = to_junit(&[report(2, 11), report(1, 1), report(2, 12)]);
+        assert_eq!(1, xml.matches("<testsuite name=\"set1\"").count());
+        assert_eq!(1, xml.matches("<testsuite name=\"set2\" tests=\"2\"").count());
+        assert!(xml.find("name=\"set1\"").unwrap() < xml.find("name=\"set2\"").unwrap());
+    }
+}
@@ -1,11 +1,11 @@
+//! helper functions used in set 1
+
 pub mod aes128lookup;
 pub mod freq;
 use storage::Storage;
 
 use std::collections::HashMap; // hashmap used in char_freq, inv_sub_bytes
 
-/// helper functions used in set 1
-
 /// hamming_distance-- helper function to calculate the hamming distance between two storages
 /// Parameters: lhs (&Storage) - left hand side storage,
 ///             rhs (&Storage) - rigth hand side storage
@@ -63,7 +63,7 @@ pub fn char_freq(str_inp: &str, freq: &HashMap<u8, f32>) -> f32 {
     str_inp
         .chars()
         .map(|c| match freq.get(&(c as u8)) {
-            Some(f) => f.clone(),
+            Some(f) => *f,
             None => 0f32,
         })
         .sum()
@@ -86,18 +86,21 @@ pub fn split_into_blocks(s: &Storage, keysize: usize) -> Vec<Storage> {
         holder[i % keysize].push(d);
     }
 
-    let dt: &str = &s.get_data_type();
+    let dt: &str = s.get_data_type();
     holder.iter().map(|v| Storage::new_init(v, dt)).collect()
 }
 
-/// calc_key_expansion_core -- TODO: finish
-pub fn calc_key_expansion_core(key: &Storage, i: usize, s_box: &Vec<u8>, rcon: &Vec<u8>) -> Storage {
+/// calc_key_expansion_core -- key schedule core applied to the last word of a round key
+/// Parameters: word (&Storage) - last 4 bytes of the previous round key
+///             round (usize) - round number of the key being generated (1 - 10)
+///             s_box (&[u8]) - AES S-Box
+///             rcon (&[u8]) - round constants
+/// Return: Storage - transformed word (4 bytes)
+pub fn calc_key_expansion_core(word: &Storage, round: usize, s_box: &[u8], rcon: &[u8]) -> Storage {
 
   // 1. Rotate left (example - [12, 62, 54, 126] -> [62, 54, 126, 12])
-  let mut v = key.get_data().clone();
-  v.swap(0, 1);
-  v.swap(1, 2);
-  v.swap(2, 3);
+  let mut v = word.get_data().clone();
+  v.rotate_left(1);
 
   // 2. S-box
   v[0] = s_box[v[0] as usize];
@@ -106,20 +109,38 @@ pub fn calc_key_expansion_core(key: &Storage, i: usize, s_box: &Vec<u8>, rcon: &
   v[3] = s_box[v[3] as usize];
 
   // 3. RCon
-  v[0] ^= rcon[i];
-  let temp: Storage = Storage::new_init_vec(&v, key.get_data_type());
+  v[0] ^= rcon[round - 1];
+  let temp: Storage = Storage::new_init_vec(&v, word.get_data_type());
   temp 
 }
 
 /// calc_key_expansion -- calculate key expansion using algorithm
 /// expands a 16 byte keys into 11 different 16 byte keys
 /// Parameters: key (&Storage) - original key
-/// Return: vec<Storage> - 11 different keys
-pub fn calc_key_expansion(keys: &mut Vec<&Storage>, s_box: &Vec<u8>, rcon: &Vec<u8>) {
-  for i in 0..10 {
-      let key_generated: Storage = calc_key_expansion_core(&keys[i], i+1, s_box, rcon);
-      keys.push(&(keys[i] ^ &key_generated));
+///             s_box (&[u8]) - AES S-Box
+///             rcon (&[u8]) - round constants
+/// Return: vec<Storage> - 11 different keys (the first one is the original key)
+pub fn calc_key_expansion(key: &Storage, s_box: &[u8], rcon: &[u8]) -> Vec<Storage> {
+  if key.len() != 16 {
+      panic!("Error: AES-128 keys are 16 bytes, this key is {} bytes", key.len());
+  }
+
+  let mut keys: Vec<Storage> = vec![Storage::new_init_vec(key.get_data(), "ascii")];
+  for round in 1..11 {
+      let prev = keys[round - 1].get_data().clone();
+      let mut word = calc_key_expansion_core(&Storage::new_init_vec(&prev[12..16], "ascii"), round, s_box, rcon)
+          .get_data()
+          .clone();
+
+      // every word is the previous word XOR'd w/ the word 16 bytes earlier
+      let mut next: Vec<u8> = Vec::new();
+      for w in 0..4 {
+          word = word.iter().zip(prev[w * 4..w * 4 + 4].iter()).map(|(a, b)| a ^ b).collect();
+          next.extend_from_slice(&word);
+      }
+      keys.push(Storage::new_init_vec(&next, "ascii"));
   }
+  keys
 }
 
 /// add_round_key -- a Round Key is added to the State by a simple
@@ -131,6 +152,68 @@ pub fn add_round_key(state: &Storage, key: &Storage) -> Storage {
     state ^ key
 }
 
+/// shift_rows -- shift to the left
+/// shift the first row 0 to the left
+/// shift the second row 1 to the left
+/// shift the third row 2 to the left
+/// shift the fourth row 3 to the left
+///
+///  B0  B4  B8 B12       B0  B4  B8 B12
+///  B1  B5  B9 B13  -->  B5  B9 B13  B1
+///  B2  B6 B10 B14  --> B10 B14  B2  B6
+///  B3  B7 B11 B15      B15  B3  B7 B11
+///
+/// Parameters: state (Storage) - 16 bytes to encrypt
+/// Return: state Storage - Bytes after shifting
+pub fn shift_rows(state: &Storage) -> Storage {
+    let b = state.get_data();
+    let d = vec![
+        b[0], b[5], b[10], b[15], b[4], b[9], b[14], b[3], b[8], b[13], b[2], b[7], b[12], b[1],
+        b[6], b[11],
+    ];
+
+    Storage::new_init_vec(&d, state.get_data_type())
+}
+
+/// sub_bytes -- subsitute bytes based on S-Box
+/// Parameters: state (Storage) - 16 bytes to encrypt
+/// Return: state Storage - Bytes after substitution
+pub fn sub_bytes(state: &Storage, s_box: &[u8]) -> Storage {
+    Storage::new_init_vec(
+        &state.get_data().iter().map(|d| s_box[*d as usize]).collect::<Vec<u8>>(),
+        state.get_data_type(),
+    )
+}
+
+/// xtime -- multiply by 2 in the gaussian field 2^8 (reduced by x^8 + x^4 + x^3 + x + 1)
+fn xtime(b: u8) -> u8 {
+    if b & 0x80 != 0 {
+        (b << 1) ^ 0x1B
+    } else {
+        b << 1
+    }
+}
+
+/// mix_columns -- MixCol by multiplying by a
+/// a = [02 03 01 01] [S_0,c]
+///     [01 02 03 01] [s_1,c]
+///     [01 01 02 03] [s_2,c]
+///     [03 01 01 02] [s_3,c]
+/// Parameters: state (Storage) - 16 bytes to encrypt
+/// Return: state Storage - Bytes after mixing
+pub fn mix_columns(state: &Storage) -> Storage {
+    let d = state.get_data();
+    let mut out = Vec::new();
+    for c in d.chunks(4) {
+        let (c0, c1, c2, c3) = (c[0], c[1], c[2], c[3]);
+        out.push(xtime(c0) ^ xtime(c1) ^ c1 ^ c2 ^ c3);
+        out.push(c0 ^ xtime(c1) ^ xtime(c2) ^ c2 ^ c3);
+        out.push(c0 ^ c1 ^ xtime(c2) ^ xtime(c3) ^ c3);
+        out.push(xtime(c0) ^ c0 ^ c1 ^ c2 ^ xtime(c3));
+    }
+    Storage::new_init_vec(&out, state.get_data_type())
+}
+
 /// inv_shift_rows -- inv shift to the right
 /// shift the first column 0 to the right
 /// shift the second column 1 to the right
@@ -163,13 +246,13 @@ pub fn inv_shift_rows(state: &Storage) -> Storage {
 /// inv_sub_bytes -- subsitute bytes based on Inverse S-Box
 /// Parameters: state (Storage) - Encrypted objected to decrypt
 /// Return: state Storage - Bytes after AES decryption
-pub fn inv_sub_bytes(state: &Storage, inverse_s_box: &Vec<u8>) -> Storage {
+pub fn inv_sub_bytes(state: &Storage, inverse_s_box: &[u8]) -> Storage {
     Storage::new_init_vec(
         &state
             .get_data()
             .iter()
             .map(|d| inverse_s_box[*d as usize])
-            .collect(),
+            .collect::<Vec<u8>>(),
         state.get_data_type(),
     )
 }
@@ -189,10 +272,10 @@ pub fn inv_sub_bytes(state: &Storage, inverse_s_box: &Vec<u8>) -> Storage {
 /// Return: state Storage - Bytes after AES decryption
 pub fn inv_mix_columns(
     state: &Storage,
-    mul_9: &Vec<u8>,
-    mul_11: &Vec<u8>,
-    mul_13: &Vec<u8>,
-    mul_14: &Vec<u8>,
+    mul_9: &[u8],
+    mul_11: &[u8],
+    mul_13: &[u8],
+    mul_14: &[u8],
 ) -> Storage {
     // TODO: figure out this length thing : prob check at beginning?
     // assuming that d len is 16
@@ -209,40 +292,106 @@ pub fn inv_mix_columns(
     Storage::new_init_vec(&out, state.get_data_type())
 }
 
-/// inv_cipher_aes_128 -- AES decyption algorithm
-/// Parameters: bytes_in (Storage) - Encrypted objected to decrypt
-///             key (&str) - Key used to encrypt object
-/// Return: state Storage - Bytes after AES decryption
-pub fn inv_cipher_aes_128(bytes_in: &Storage, key: &Storage) {
-    if bytes_in.len() % 16 != 0 {
-        panic!("Error: the length of bytes_in must be divisible by 16");
+/// cipher_aes_128_block -- AES encryption of a single block
+/// Parameters: block (&Storage) - 16 bytes to encrypt (ascii)
+///             keys (&[Storage]) - 11 round keys from calc_key_expansion
+///             s_box (&[u8]) - AES S-Box
+/// Return: Storage - encrypted block (ascii)
+pub fn cipher_aes_128_block(block: &Storage, keys: &[Storage], s_box: &[u8]) -> Storage {
+    if block.len() != 16 {
+        panic!("Error: AES blocks are 16 bytes, this block is {} bytes", block.len());
     }
-    let (s_box, inverse_s_box, rcon, mul_9, mul_11, mul_13, mul_14) = aes128lookup::get_aes_128_lookup_tables();
-    let end: usize = bytes_in.len() / 16;
-
-    
-    let mut keys: Vec<&Storage> = Vec::new();
-    keys.push(key);
-    calc_key_expansion(&mut keys, &s_box, &rcon);
+    let mut state = add_round_key(block, &keys[0]);
 
-    for i in 0usize..end {
-        let mut state = bytes_in.index(i * 16, (i + 1) * 16);
+    for key in &keys[1..10] {
+        state = sub_bytes(&state, s_box);
+        state = shift_rows(&state);
+        state = mix_columns(&state);
+        state = add_round_key(&state, key);
+    }
 
-        state = add_round_key(&state, &key);
+    state = sub_bytes(&state, s_box);
+    state = shift_rows(&state);
+    add_round_key(&state, &keys[10])
+}
 
-        for _j in 0..9 {
-            state = inv_shift_rows(&state);
-            state = inv_sub_bytes(&state, &inverse_s_box);
-            state = add_round_key(&state, &key);
-            state = inv_mix_columns(&state, &mul_9, &mul_11, &mul_13, &mul_14);
-        }
+/// inv_cipher_aes_128_block -- AES decryption of a single block
+/// Parameters: block (&Storage) - 16 bytes to decrypt (ascii)
+///             keys (&[Storage]) - 11 round keys from calc_key_expansion
+///             inverse_s_box, mul_9, mul_11, mul_13, mul_14 (&[u8]) - lookup tables
+/// Return: Storage - decrypted block (ascii)
+pub fn inv_cipher_aes_128_block(
+    block: &Storage,
+    keys: &[Storage],
+    inverse_s_box: &[u8],
+    mul_9: &[u8],
+    mul_11: &[u8],
+    mul_13: &[u8],
+    mul_14: &[u8],
+) -> Storage {
+    if block.len() != 16 {
+        panic!("Error: AES blocks are 16 bytes, this block is {} bytes", block.len());
+    }
+    let mut state = add_round_key(block, &keys[10]);
 
+    for round in (1..10).rev() {
         state = inv_shift_rows(&state);
-        state = inv_sub_bytes(&state, &inverse_s_box);
-        state = add_round_key(&state, &key);
+        state = inv_sub_bytes(&state, inverse_s_box);
+        state = add_round_key(&state, &keys[round]);
+        state = inv_mix_columns(&state, mul_9, mul_11, mul_13, mul_14);
+    }
+
+    state = inv_shift_rows(&state);
+    state = inv_sub_bytes(&state, inverse_s_box);
+    add_round_key(&state, &keys[0])
+}
 
-        state.print();
+/// cipher_aes_128 -- AES encryption of every block on its own (ECB)
+/// Parameters: bytes_in (Storage) - Object to encrypt (multiple of 16 bytes)
+///             key (&Storage) - 16 byte key
+/// Return: state Storage - Bytes after AES encryption (ascii)
+pub fn cipher_aes_128(bytes_in: &Storage, key: &Storage) -> Storage {
+    if !bytes_in.len().is_multiple_of(16) {
+        panic!("Error: the length of bytes_in must be divisible by 16");
+    }
+    let (s_box, _, rcon, _, _, _, _) = aes128lookup::get_aes_128_lookup_tables();
+    let keys = calc_key_expansion(key, &s_box, &rcon);
+    let bytes_in = Storage::new_init_vec(bytes_in.get_data(), "ascii");
+
+    let mut out: Vec<u8> = Vec::new();
+    for i in 0..bytes_in.len() / 16 {
+        let state = cipher_aes_128_block(&bytes_in.index(i * 16, (i + 1) * 16), &keys, &s_box);
+        out.extend_from_slice(state.get_data());
     }
+    Storage::new_init_vec(&out, "ascii")
+}
+
+/// inv_cipher_aes_128 -- AES decyption of every block on its own (ECB)
+/// Parameters: bytes_in (Storage) - Encrypted objected to decrypt (multiple of 16 bytes)
+///             key (&Storage) - Key used to encrypt object
+/// Return: state Storage - Bytes after AES decryption (ascii)
+pub fn inv_cipher_aes_128(bytes_in: &Storage, key: &Storage) -> Storage {
+    if !bytes_in.len().is_multiple_of(16) {
+        panic!("Error: the length of bytes_in must be divisible by 16");
+    }
+    let (s_box, inverse_s_box, rcon, mul_9, mul_11, mul_13, mul_14) = aes128lookup::get_aes_128_lookup_tables();
+    let keys = calc_key_expansion(key, &s_box, &rcon);
+    let bytes_in = Storage::new_init_vec(bytes_in.get_data(), "ascii");
+
+    let mut out: Vec<u8> = Vec::new();
+    for i in 0..bytes_in.len() / 16 {
+        let state = inv_cipher_aes_128_block(
+            &bytes_in.index(i * 16, (i + 1) * 16),
+            &keys,
+            &inverse_s_box,
+            &mul_9,
+            &mul_11,
+            &mul_13,
+            &mul_14,
+        );
+        out.extend_from_slice(state.get_data());
+    }
+    Storage::new_init_vec(&out, "ascii")
 }
 
 #[cfg(test)]
@@ -294,38 +443,20 @@ mod tests {
     #[test]
     fn check_char_freq_compare_two_strings() {
         let freq = freq::get_char_freq_table();
-        assert_eq!(
-            char_freq("hello world", &freq) > char_freq("~!#$!@", &freq),
-            true
-        );
-        assert_eq!(
-            char_freq("this is a secret message", &freq) > char_freq("~!#$!@", &freq),
-            true
-        );
-        assert_eq!(
-            char_freq("key", &freq) > char_freq("!@#()!#$,./", &freq),
-            true
-        );
-        assert_eq!(
-            char_freq("blah blahBLAH", &freq) > char_freq("~!#$!@", &freq),
-            true
-        );
+        assert!(char_freq("hello world", &freq) > char_freq("~!#$!@", &freq));
+        assert!(char_freq("this is a secret message", &freq) > char_freq("~!#$!@", &freq));
+        assert!(char_freq("key", &freq) > char_freq("!@#()!#$,./", &freq));
+        assert!(char_freq("blah blahBLAH", &freq) > char_freq("~!#$!@", &freq));
     }
 
     #[test]
     fn check_char_freq_tests_that_should_fail() {
         let freq = freq::get_char_freq_table();
         // checking valid string vs white space (invalid)
-        assert_ne!(
-            char_freq("hello world", &freq) > char_freq("           ", &freq),
-            true
-        );
+        assert!(char_freq("hello world", &freq) <= char_freq("           ", &freq));
 
         // checking length of valid string vs invalid string
-        assert_ne!(
-            char_freq("key", &freq) > char_freq("    !@# ,,. )(@! ", &freq),
-            true
-        );
+        assert!(char_freq("key", &freq) <= char_freq("    !@# ,,. )(@! ", &freq));
     }
 
     // TEST split_into_blocks
@@ -352,68 +483,76 @@ mod tests {
     #[test]
     fn check_key_expansion() {
         let (s_box, _, rcon, _, _, _, _) = aes128lookup::get_aes_128_lookup_tables();
-        let test1_key = Storage::new_init("00000000000000000000000000000000", "base64");
+        let test1_key = Storage::new_init("00000000000000000000000000000000", "hex");
         let test1_ans: Vec<Storage> = vec![
-          Storage::new_init("00000000000000000000000000000000", "base64"),
-          Storage::new_init("62636363626363636263636362636363", "base64"),
-          Storage::new_init("9b9898c9f9fbfbaa9b9898c9f9fbfbaa", "base64"),
-          Storage::new_init("90973450696ccffaf2f457330b0fac99", "base64"),
-          Storage::new_init("ee06da7b876a1581759e42b27e91ee2b", "base64"),
-          Storage::new_init("7f2e2b88f8443e098dda7cbbf34b9290", "base64"),
-          Storage::new_init("ec614b851425758c99ff09376ab49ba7", "base64"),
-          Storage::new_init("217517873550620bacaf6b3cc61bf09b", "base64"),
-          Storage::new_init("0ef903333ba9613897060a04511dfa9f", "base64"),
-          Storage::new_init("b1d4d8e28a7db9da1d7bb3de4c664941", "base64"),
-          Storage::new_init("b4ef5bcb3e92e21123e951cf6f8f188e", "base64"),
+          Storage::new_init("00000000000000000000000000000000", "hex"),
+          Storage::new_init("62636363626363636263636362636363", "hex"),
+          Storage::new_init("9b9898c9f9fbfbaa9b9898c9f9fbfbaa", "hex"),
+          Storage::new_init("90973450696ccffaf2f457330b0fac99", "hex"),
+          Storage::new_init("ee06da7b876a1581759e42b27e91ee2b", "hex"),
+          Storage::new_init("7f2e2b88f8443e098dda7cbbf34b9290", "hex"),
+          Storage::new_init("ec614b851425758c99ff09376ab49ba7", "hex"),
+          Storage::new_init("217517873550620bacaf6b3cc61bf09b", "hex"),
+          Storage::new_init("0ef903333ba9613897060a04511dfa9f", "hex"),
+          Storage::new_init("b1d4d8e28a7db9da1d7bb3de4c664941", "hex"),
+          Storage::new_init("b4ef5bcb3e92e21123e951cf6f8f188e", "hex"),
         ];
-        let mut test1_keys: Vec<&Storage> = Vec::new();
-        test1_keys.push(&test1_key);
-        calc_key_expansion(&mut test1_keys, &s_box, &rcon);
-        assert_eq!(test1_keys.len(), test1_ans.len());
         
-        let test2_key = Storage::new_init("ffffffffffffffffffffffffffffffff", "base64");
+        let test2_key = Storage::new_init("ffffffffffffffffffffffffffffffff", "hex");
         let test2_ans: Vec<Storage> = vec![
-          Storage::new_init("ffffffffffffffffffffffffffffffff", "base64"),
-          Storage::new_init("e8e9e9e917161616e8e9e9e917161616", "base64"),
-          Storage::new_init("adaeae19bab8b80f525151e6454747f0", "base64"),
-          Storage::new_init("090e2277b3b69a78e1e7cb9ea4a08c6e", "base64"),
-          Storage::new_init("e16abd3e52dc2746b33becd8179b60b6", "base64"),
-          Storage::new_init("e5baf3ceb766d488045d385013c658e6", "base64"),
-          Storage::new_init("71d07db3c6b6a93bc2eb916bd12dc98d", "base64"),
-          Storage::new_init("e90d208d2fbb89b6ed5018dd3c7dd150", "base64"),
-          Storage::new_init("96337366b988fad054d8e20d68a5335d", "base64"),
-          Storage::new_init("8bf03f233278c5f366a027fe0e0514a3", "base64"),
-          Storage::new_init("d60a3588e472f07b82d2d7858cd7c326", "base64"),
+          Storage::new_init("ffffffffffffffffffffffffffffffff", "hex"),
+          Storage::new_init("e8e9e9e917161616e8e9e9e917161616", "hex"),
+          Storage::new_init("adaeae19bab8b80f525151e6454747f0", "hex"),
+          Storage::new_init("090e2277b3b69a78e1e7cb9ea4a08c6e", "hex"),
+          Storage::new_init("e16abd3e52dc2746b33becd8179b60b6", "hex"),
+          Storage::new_init("e5baf3ceb766d488045d385013c658e6", "hex"),
+          Storage::new_init("71d07db3c6b6a93bc2eb916bd12dc98d", "hex"),
+          Storage::new_init("e90d208d2fbb89b6ed5018dd3c7dd150", "hex"),
+          Storage::new_init("96337366b988fad054d8e20d68a5335d", "hex"),
+          Storage::new_init("8bf03f233278c5f366a027fe0e0514a3", "hex"),
+          Storage::new_init("d60a3588e472f07b82d2d7858cd7c326", "hex"),
         ];
         
-        let test3_key = Storage::new_init("000102030405060708090a0b0c0d0e0f", "base64");
+        let test3_key = Storage::new_init("000102030405060708090a0b0c0d0e0f", "hex");
         let test3_ans: Vec<Storage> = vec![
-          Storage::new_init("000102030405060708090a0b0c0d0e0f", "base64"),
-          Storage::new_init("d6aa74fdd2af72fadaa678f1d6ab76fe", "base64"),
-          Storage::new_init("b692cf0b643dbdf1be9bc5006830b3fe", "base64"),
-          Storage::new_init("b6ff744ed2c2c9bf6c590cbf0469bf41", "base64"),
-          Storage::new_init("47f7f7bc95353e03f96c32bcfd058dfd", "base64"),
-          Storage::new_init("3caaa3e8a99f9deb50f3af57adf622aa", "base64"),
-          Storage::new_init("5e390f7df7a69296a7553dc10aa31f6b", "base64"),
-          Storage::new_init("14f9701ae35fe28c440adf4d4ea9c026", "base64"),
-          Storage::new_init("47438735a41c65b9e016baf4aebf7ad2", "base64"),
-          Storage::new_init("549932d1f08557681093ed9cbe2c974e", "base64"),
-          Storage::new_init("13111d7fe3944a17f307a78b4d2b30c5", "base64"),
+          Storage::new_init("000102030405060708090a0b0c0d0e0f", "hex"),
+          Storage::new_init("d6aa74fdd2af72fadaa678f1d6ab76fe", "hex"),
+          Storage::new_init("b692cf0b643dbdf1be9bc5006830b3fe", "hex"),
+          Storage::new_init("b6ff744ed2c2c9bf6c590cbf0469bf41", "hex"),
+          Storage::new_init("47f7f7bc95353e03f96c32bcfd058dfd", "hex"),
+          Storage::new_init("3caaa3e8a99f9deb50f3af57adf622aa", "hex"),
+          Storage::new_init("5e390f7df7a69296a7553dc10aa31f6b", "hex"),
+          Storage::new_init("14f9701ae35fe28c440adf4d4ea9c026", "hex"),
+          Storage::new_init("47438735a41c65b9e016baf4aebf7ad2", "hex"),
+          Storage::new_init("549932d1f08557681093ed9cbe2c974e", "hex"),
+          Storage::new_init("13111d7fe3944a17f307a78b4d2b30c5", "hex"),
         ];
 
-        let test4_key = Storage::new_init("6920e299a5202a6d656e636869746f2a", "base64");
+        let test4_key = Storage::new_init("6920e299a5202a6d656e636869746f2a", "hex");
         let test4_ans: Vec<Storage> = vec![
-          Storage::new_init("6920e299a5202a6d656e636869746f2a", "base64"),
-          Storage::new_init("fa8807605fa82d0d3ac64e6553b2214f", "base64"),
-          Storage::new_init("cf75838d90ddae80aa1be0e5f9a9c1aa", "base64"),
-          Storage::new_init("180d2f1488d0819422cb6171db62a0db", "base64"),
-          Storage::new_init("baed96ad323d173910f67648cb94d693", "base64"),
-          Storage::new_init("881b4ab2ba265d8baad02bc36144fd50", "base64"),
-          Storage::new_init("b34f195d096944d6a3b96f15c2fd9245", "base64"),
-          Storage::new_init("a7007778ae6933ae0dd05cbbcf2dcefe", "base64"),
-          Storage::new_init("ff8bccf251e2ff5c5c32a3e7931f6d19", "base64"),
-          Storage::new_init("24b7182e7555e77229674495ba78298c", "base64"),
-          Storage::new_init("ae127cdadb479ba8f220df3d4858f6b1", "base64"),
+          Storage::new_init("6920e299a5202a6d656e636869746f2a", "hex"),
+          Storage::new_init("fa8807605fa82d0d3ac64e6553b2214f", "hex"),
+          Storage::new_init("cf75838d90ddae80aa1be0e5f9a9c1aa", "hex"),
+          Storage::new_init("180d2f1488d0819422cb6171db62a0db", "hex"),
+          Storage::new_init("baed96ad323d173910f67648cb94d693", "hex"),
+          Storage::new_init("881b4ab2ba265d8baad02bc36144fd50", "hex"),
+          Storage::new_init("b34f195d096944d6a3b96f15c2fd9245", "hex"),
+          Storage::new_init("a7007778ae6933ae0dd05cbbcf2dcefe", "hex"),
+          Storage::new_init("ff8bccf251e2ff5c5c32a3e7931f6d19", "hex"),
+          Storage::new_init("24b7182e7555e77229674495ba78298c", "hex"),
+          Storage::new_init("ae127cdadb479ba8f220df3d4858f6b1", "hex"),
         ];
+
+        for (key, ans) in [(test1_key, test1_ans), (test2_key, test2_ans), (test3_key, test3_ans), (test4_key, test4_ans)] {
+            let mut key = key;
+            key.change_base("ascii");
+            let keys = calc_key_expansion(&key, &s_box, &rcon);
+            assert_eq!(keys.len(), ans.len());
+            for (k, a) in keys.iter().zip(ans.iter()) {
+                let mut k = Storage::new_init_vec(k.get_data(), "ascii");
+                k.change_base("hex");
+                assert_eq!(a.to_string(), k.to_string());
+            }
+        }
     }
 }
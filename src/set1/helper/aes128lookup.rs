@@ -1,3 +1,6 @@
+/// LookupTables -- (s_box, inverse_s_box, rcon, mul_9, mul_11, mul_13, mul_14)
+pub type LookupTables = (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>);
+
 /* get_aes_128_lookup_tables -- helper function that return gaussian field multiplication lookup tables
  * as well as gaussian field mult and affine transformation for inverse byte substitution
  * We ideally want to write this to memory once
@@ -12,7 +15,7 @@
  *         mul_13 (Vec<u8>) - Gaussian field (2^8) of multiplication 13
  *         mul_14 (Vec<u8>) - Gaussian field (2^8) of multiplication 14
  */
-pub fn get_aes_128_lookup_tables() -> (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>) {
+pub fn get_aes_128_lookup_tables() -> LookupTables {
     let s_box: Vec<u8> = vec![
         0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab,
         0x76, 0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4,
@@ -1,3 +1,5 @@
+use fixtures;
+
 use std::collections::HashMap; // hashmap used in char_freq
 
 /* get_char_freq_table -- helper function that return char freq lookup table
@@ -113,3 +115,59 @@ pub fn get_char_freq_table() -> HashMap<u8, f32> {
         .collect();
     freq
 }
+
+/* get_letter_freq_table -- helper function that returns the frequency of each letter
+ * (upper and lower case combined) taken from get_char_freq_table
+ * Parameters: void
+ * Return: freq (Vec<f64>) - 26 frequencies (A-Z) that add up to 1.0
+ */
+pub fn get_letter_freq_table() -> Vec<f64> {
+    let freq = get_char_freq_table();
+    let letters: Vec<f64> = (0u8..26u8)
+        .map(|i| (freq[&(b'A' + i)] + freq[&(b'a' + i)]) as f64)
+        .collect();
+    let total: f64 = letters.iter().sum();
+    letters.iter().map(|f| f / total).collect()
+}
+
+/* get_quadgram_table -- helper function that returns log10 probabilities of every
+ * sequence of four letters, counted from the public domain prose in input_files/english.txt
+ * Each entry is the probability of the last letter given the first three.  Quadgrams that
+ * never appear back off to the shorter sequence (times 0.4) so unseen text is still ranked.
+ * Parameters: void
+ * Return: quadgrams (Vec<f64>) - 26^4 log10 probabilities indexed by
+ *         ((a * 26 + b) * 26 + c) * 26 + d where a-d are letters (A = 0)
+ */
+pub fn get_quadgram_table() -> Vec<f64> {
+    let corpus: Vec<usize> = fixtures::bundled("english.txt")
+        .bytes()
+        .filter(|b| b.is_ascii_alphabetic())
+        .map(|b| (b.to_ascii_uppercase() - b'A') as usize)
+        .collect();
+
+    // counts[n - 1] holds the count of every sequence of n letters
+    let mut counts: Vec<Vec<f64>> = (1..5u32).map(|n| vec![0f64; 26usize.pow(n)]).collect();
+    for n in 1..5 {
+        for w in corpus.windows(n) {
+            counts[n - 1][w.iter().fold(0, |i, l| i * 26 + l)] += 1.0;
+        }
+    }
+
+    // probability of the last letter of the sequence given the letters before it
+    fn backoff(counts: &Vec<Vec<f64>>, total: f64, index: usize, n: usize) -> f64 {
+        if n == 1 {
+            return counts[0][index].max(0.01) / total;
+        }
+        let c = counts[n - 1][index];
+        if c > 0.0 {
+            c / counts[n - 2][index / 26]
+        } else {
+            0.4 * backoff(counts, total, index % 26usize.pow(n as u32 - 1), n - 1)
+        }
+    }
+
+    let total = corpus.len().max(1) as f64;
+    (0..26 * 26 * 26 * 26)
+        .map(|i| backoff(&counts, total, i, 4).log10())
+        .collect()
+}
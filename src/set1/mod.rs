@@ -1,212 +1,247 @@
 pub mod helper;
 
-use storage::Storage;
-use challenge::Challenge;
-use challenge::Builder;
-
-use std::f64;
-use std::fs;
+use storage::{Encoding, Storage};
+use aes;
+use fixtures;
+use xor;
+use challenge::{Builder, Challenge, Outcome, Overrides};
+
+/// challenges -- every implemented challenge in set 1 (see challenge::registry)
+/// Parameters: void
+/// Return: Vec<Box<dyn Challenge>> - set 1 challenges
+pub fn challenges() -> Vec<Box<dyn Challenge>> {
+    vec![
+        Box::new(Challenge1),
+        Box::new(Challenge2),
+        Box::new(Challenge3),
+        Box::new(Challenge4),
+        Box::new(Challenge5),
+        Box::new(Challenge6),
+        Box::new(Challenge7),
+    ]
+}
 
-/// challenge1 -- Hex to Base64
+/// Challenge1 -- Hex to Base64
 /// http://cryptopals.com/sets/1/challenges/1
 /// Convert Hex to Base64
 /// Always operate on raw bytes, never on encoded strings.
-pub fn challenge1() {
-    // Definitions
-    let mut builder = Builder::new();
-    let challenge = builder
-        .header("Set 1, Challenge 1 - hex to base64")
-        .input("49276d206b696c6c696e6720796f757220627261696e206c696b65206120\
-         706f69736f6e6f7573206d757368726f6f6d")
-        .input_type("hex")
-        .expected_answer("SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t")
-        .expected_type("base64")
-        .build();
-
-    // Work
-    let mut ans = Storage::new_init(challenge.get_input(), challenge.get_input_type());
-    ans.change_base(challenge.get_expected_type());
-
-    // Output
-    challenge.set_actual_answer(ans.to_str());
-    challenge.set_actual_type(ans.get_data_type());
-    challenge.print();
+pub struct Challenge1;
+
+impl Challenge for Challenge1 {
+    fn id(&self) -> u32 {
+        1
+    }
+
+    fn set(&self) -> u32 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "hex to base64"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input(Storage::new_init("49276d206b696c6c696e6720796f757220627261696e206c696b65206120\
+             706f69736f6e6f7573206d757368726f6f6d", "hex"))
+            .output(Encoding::Base64)
+            .expect("answer", "SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t")
+            .overrides(overrides)
+            .build();
+
+        // Work
+        let mut ans = outcome.get_input();
+        ans.change_base(outcome.get_output().as_str());
+
+        // Output
+        outcome.set_actual("answer", ans.to_string());
+        outcome
+    }
 }
 
-/// challenge2 -- Fixed XOR 
+/// Challenge2 -- Fixed XOR 
 /// http://cryptopals.com/sets/1/challenges/2
 /// xor on two hex strings
 /// lhs_str (&str) - left hand side input
 /// lhs_type (&str) - left hand side data type (hex/base64)
 /// rhs_str (&str) - right hand side input
 /// rhs_type (&str) - right hand side data type (hex/base64)
-pub fn challenge2() {
-    // Definitions
-    let mut builder = Builder::new();
-    let challenge = builder
-        .header("Set 1, Challenge 2 - fixed XOR")
-        .input("1c0111001f010100061a024b53535009181c")
-        .input_type("hex")
-        .input2("686974207468652062756c6c277320657965")
-        .input2_type("hex")
-        .expected_answer("746865206b696420646f6e277420706c6179")
-        .expected_type("hex")
-        .build();
-
-    // Work
-    let lhs = Storage::new_init(challenge.get_input(), challenge.get_input_type());
-    let rhs = Storage::new_init(challenge.get_input2(), challenge.get_input2_type());
-    let ans = &lhs ^ &rhs;
-
-    // Output
-    challenge.set_actual_answer(ans.to_str());
-    challenge.set_actual_type(ans.get_data_type());
-    challenge.print();
+pub struct Challenge2;
+
+impl Challenge for Challenge2 {
+    fn id(&self) -> u32 {
+        2
+    }
+
+    fn set(&self) -> u32 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "fixed XOR"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input(Storage::new_init("1c0111001f010100061a024b53535009181c", "hex"))
+            .input2(Storage::new_init("686974207468652062756c6c277320657965", "hex"))
+            .output(Encoding::Hex)
+            .expect("answer", "746865206b696420646f6e277420706c6179")
+            .overrides(overrides)
+            .build();
+
+        // Work
+        let lhs = outcome.get_input();
+        let rhs = outcome.get_input2().expect("Error: fixed XOR needs a second input");
+        let mut ans = &lhs ^ rhs;
+        ans.change_base(outcome.get_output().as_str());
+
+        // Output
+        outcome.set_actual("answer", ans.to_string());
+        outcome
+    }
 }
 
-/// challenge3 - Single Byte Xor Cipher
+/// Challenge3 -- Single Byte Xor Cipher
 /// http://cryptopals.com/sets/1/challenges/3
 /// The hex string has been XOR'd against a single character.
 /// Find the key, decrypt the message.
-pub fn challenge3() {
-    // Definitions
-    let mut builder = Builder::new();
-    let challenge = builder
-        .header("Set 1, Challenge 3 - Single-byte XOR cipher")
-        .input("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736")
-        .input_type("hex")
-        .expected_answer("Cooking MC's like a pound of bacon")
-        .expected_key("X")
-        .build();
-
-    let s = Storage::new_init(challenge.get_input(), challenge.get_input_type());
-    let freq = helper::freq::get_char_freq_table();
-    let mut result_string: String = s.to_string();
-    let mut result_char: char = '0';
-    let mut max_freq: f32 = 0_f32;
-    let mut tmp_freq: f32;
-
-    // TODO: make helper func that gets 0-9a-zA-Z vec of storages
-    // Work
-    for i in "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz".chars() {
-        let mut char_obj = Storage::new_init(&i.to_string(), "ascii");
-        char_obj.change_base("hex");
-        let mut ans = &s ^ &char_obj;
-        ans.change_base("ascii");
-
-        tmp_freq = helper::char_freq(ans.to_string().as_str(), &freq);
-        if tmp_freq > max_freq {
-            result_string = ans.to_string();
-            result_char = i;
-            max_freq = tmp_freq;
-        }
+pub struct Challenge3;
+
+impl Challenge for Challenge3 {
+    fn id(&self) -> u32 {
+        3
+    }
+
+    fn set(&self) -> u32 {
+        1
     }
 
-    // Output
-    challenge.set_actual_answer(&result_string);
-    challenge.set_actual_key(&result_char.to_string());
-    challenge.print();
+    fn title(&self) -> &'static str {
+        "Single-byte XOR cipher"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input(Storage::new_init("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736", "hex"))
+            .expect("answer", "Cooking MC's like a pound of bacon")
+            .expect("key", "X")
+            .overrides(overrides)
+            .build();
+
+        let s = outcome.get_input();
+        let freq = helper::freq::get_char_freq_table();
+
+        // Work
+        let ans = xor::break_single_byte(&s, &freq);
+
+        // Output
+        outcome.set_actual("answer", ans.plaintext.to_string());
+        outcome.set_actual("key", (ans.key as char).to_string());
+        outcome
+    }
 }
 
-/// challenge4 -- Detect Single Character Xor
+/// Challenge4 -- Detect Single Character Xor
 /// http://cryptopals.com/sets/1/challenges/4
 /// One of the 60-character strings in this file has been encrypted by single-character XOR. Find it.
 /// Parameters: filename(&str) - File to detect single-character XOR
-/// Return: (String, char, i32) - (Secret message, key that was used, line number)
-pub fn challenge4() {
-    // Definitions
-    let mut builder = Builder::new();
-    let challenge = builder
-        .header("Set 1, Challenge 4 - Detect single-character XOR")
-        .input_file("./input_files/set1challenge4.txt")
-        .input_type("hex")
-        .expected_answer("Now that the party is jumping\n")
-        .expected_key("5")
-        .expected_line(5)
-        .build();
-
-    let contents = fs::read_to_string(challenge.get_input_file()).expect("Error: Unable to read file");
-    let file_contents: Vec<Storage> = contents
-        .lines()
-        .map(|l| Storage::new_init(l, challenge.get_input_type()))
-        .collect();
-
-    let char_objs: Vec<Storage> = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"
-        .chars()
-        .map(|c| Storage::new_init(&c.to_string(), "ascii"))
-        .collect();
-    let freq = helper::freq::get_char_freq_table();
-
-    // results that are going to be returned
-    let mut result_string: String = String::new();
-    let mut result_char: String = String::new();
-    let mut result_num: i32 = 0;
-
-    let mut max_freq: f32 = 0_f32; // keep track of the winner char_freq
-    let mut tmp_freq: f32; // tmp variable to store char_freq of current string
-    let mut count: i32 = 0; // keep track of line number
-    let mut ans: Storage;
-
-    // Work
-    for mut fc in file_contents {
-        fc.change_base("ascii");
-        for co in &char_objs {
-            ans = &fc ^ co;
-            tmp_freq = helper::char_freq(&ans.to_string().as_str(), &freq);
-
-            if tmp_freq > max_freq {
-                result_string = ans.to_string();
-                result_char = co.to_string();
-                result_num = count;
-                max_freq = tmp_freq;
-            }
-        }
-        count += 1;
+/// Return: (String, char, i32) - (Secret message, key that was used, line number starting at 1)
+pub struct Challenge4;
+
+impl Challenge for Challenge4 {
+    fn id(&self) -> u32 {
+        4
+    }
+
+    fn set(&self) -> u32 {
+        1
     }
 
-    // Output
-    challenge.set_actual_answer(&result_string);
-    challenge.set_actual_key(&result_char.to_string());
-    challenge.set_actual_line(result_num);
-    challenge.print();
+    fn title(&self) -> &'static str {
+        "Detect single-character XOR"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input_fixture("set1challenge4.txt", Encoding::Hex)
+            .expect("answer", "Now that the party is jumping\n")
+            .expect("key", "5")
+            .expect("line", 171)
+            .overrides(overrides)
+            .build();
+
+        let file_contents = outcome.get_input_lines();
+
+        // Work
+        let found = xor::detect_single_byte(&file_contents, -4.0, 0);
+        let best = found.first().expect("Error: No line decrypts to english");
+
+        // Output
+        outcome.set_actual("answer", best.plaintext.to_string());
+        outcome.set_actual("key", (best.key as char).to_string());
+        outcome.set_actual("line", best.line);
+        outcome
+    }
 }
 
-/// challenge5 -- Repeating Key Xor Encrypt
+/// Challenge5 -- Repeating Key Xor Encrypt
 /// http://cryptopals.com/sets/1/challenges/5
 /// Parameters: lhs_str (&str) - left hand side input
 ///             lhs_type (&str) - left hand side data type (hex/base64)
 ///             rhs_str (&str) - right hand side input
 ///             rhs_type (&str) - right hand side data type (hex/base64)
 /// Return: String - Encrypted message
-pub fn challenge5() {
-    // TODO: handle \n -- newlines in storage / extract out
-    // Definitions
-    let mut builder = Builder::new();
-    let challenge = builder
-        .header("Set 1, Challenge 5 - repeating-key XOR")
-        .input("Burning 'em, if you ain't quick and nimble\nI go crazy \
-        when I hear a cymbal")
-        .input_type("ascii")
-        .key("ICE")
-        .key_type("ascii")
-        .expected_answer("0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a2622\
-         6324272765272a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f")
-        .expected_type("hex")
-        .build();
-    let lhs = Storage::new_init(challenge.get_input(), challenge.get_input_type());
-    let rhs = Storage::new_init(challenge.get_key(), challenge.get_key_type());
-
-    // Work
-    let mut ans = &lhs ^ &rhs;
-    ans.change_base(challenge.get_expected_type());
-
-    // Output
-    challenge.set_actual_answer(ans.to_str());
-    challenge.set_actual_type(ans.get_data_type());
-    challenge.print();
+pub struct Challenge5;
+
+impl Challenge for Challenge5 {
+    fn id(&self) -> u32 {
+        5
+    }
+
+    fn set(&self) -> u32 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "repeating-key XOR"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // TODO: handle \n -- newlines in storage / extract out
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input(Storage::new_init("Burning 'em, if you ain't quick and nimble\nI go crazy \
+            when I hear a cymbal", "ascii"))
+            .key(Storage::new_init("ICE", "ascii"))
+            .output(Encoding::Hex)
+            .expect("answer", "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a2622\
+             6324272765272a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f")
+            .overrides(overrides)
+            .build();
+        let lhs = outcome.get_input();
+        let rhs = outcome.get_key().expect("Error: repeating-key XOR needs a key");
+
+        // Work
+        let mut ans = &lhs ^ rhs;
+        ans.change_base(outcome.get_output().as_str());
+
+        // Output
+        outcome.set_actual("answer", ans.to_string());
+        outcome
+    }
 }
 
-/// challenge6 -- Break Repeating Key Xor
+/// Challenge6 -- Break Repeating Key Xor
 /// http://cryptopals.com/sets/1/challenges/6
 /// File has been base64'd after being encrypted with repeating-key XOR.
 /// Algorithm:
@@ -227,89 +262,48 @@ pub fn challenge5() {
 ///          the repeating-key XOR key byte for that block. Put them together and you have the key.
 /// Parameters: filename(&str) - File to detect repeating key xor
 /// Return: (String, String, usize) - (Secret message, key that was used, key size)
-pub fn challenge6() {
-    // Definitions
-    let mut builder = Builder::new();
-    let challenge = builder
-        .header("Set 1, Challenge 6 - Break repeating-key XOR")
-        .input_file("./input_files/set1challenge6.txt")
-        .input_type("base64")
-        .expected_answer("Terminator X: Bring the noise")
-        // TODO: key?
-        .expected_key("")
-        .expected_size(29)
-        .build();
-
-    let contents = fs::read_to_string(challenge.get_input_file()).expect("Error: Unable to read file");
-    let mut file_contents = Storage::new_init(&contents.replace("\n", ""), challenge.get_input_type());
-    file_contents.change_base("ascii");
-
-    let char_objs: Vec<Storage> =
-        " 0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz:"
-            .chars()
-            .map(|c| Storage::new_init(&c.to_string(), "ascii"))
-            .collect();
-
-    let freq = helper::freq::get_char_freq_table();
-
-    // Work
-    // Step 1-4 - Figure out keysize (theoretically we should use a minheap)
-    let mut keysize: usize = 0;
-    let mut min_nor_dist: f64 = f64::INFINITY; // set as MAX
-    let mut tmp: f64;
-
-    for i in 4usize..41usize {
-        let s1 = file_contents.index(0, i);
-        let s2 = file_contents.index(i, 2 * i);
-        let s3 = file_contents.index(2 * i, 3 * i);
-        let s4 = file_contents.index(3 * i, 4 * i);
-
-        let hd1 = helper::hamming_distance(&s1, &s2);
-        let hd2 = helper::hamming_distance(&s2, &s3);
-        let hd3 = helper::hamming_distance(&s3, &s4);
-
-        tmp = (hd1 + hd2 + hd3) as f64 / (3 * i) as f64;
-
-        if tmp < min_nor_dist {
-            min_nor_dist = tmp;
-            keysize = i;
-        }
-    }
-
-    let mut key_string = String::new();
-    let mut result_char = String::new();
-    let mut max_freq: f32;
-    let mut tmp_freq: f32;
-    let mut ans;
+pub struct Challenge6;
 
-    let blocks = helper::split_into_blocks(&file_contents, keysize);
-
-    for block in &blocks {
-        max_freq = 0_f32;
-
-        for co in &char_objs {
-            ans = block ^ co;
-            tmp_freq = helper::char_freq(&ans.to_string().as_str(), &freq);
+impl Challenge for Challenge6 {
+    fn id(&self) -> u32 {
+        6
+    }
 
-            if tmp_freq > max_freq {
-                result_char = co.to_string();
-                max_freq = tmp_freq;
-            }
-        }
-        key_string.push_str(&result_char);
+    fn set(&self) -> u32 {
+        1
     }
 
-    let key_obj = Storage::new_init(&key_string, "ascii");
-    ans = &file_contents ^ &key_obj;
+    fn title(&self) -> &'static str {
+        "Break repeating-key XOR"
+    }
 
-    // Output
-    challenge.set_actual_answer(ans.to_str());
-    challenge.set_actual_key(&key_string);
-    challenge.set_actual_size(keysize);
-    challenge.print();
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input_fixture("set1challenge6.txt", Encoding::Base64)
+            .expect("key", "Terminator X: Bring the noise")
+            .expect("size", 29)
+            .overrides(overrides)
+            .build();
+
+        let mut file_contents = outcome.get_input();
+        file_contents.change_base("ascii");
+
+        // Work
+        // Step 1-4 - rank key sizes by hamming distance averaged over every pair of blocks
+        // Step 5-8 - solve the transposed blocks of the 3 best key sizes as single-byte XOR
+        let ans = xor::break_repeating_key(&file_contents, 2, 40, 3, xor::KeySizeMethod::HammingDistance);
+
+        // Output
+        outcome.set_actual("answer", ans.plaintext.to_string());
+        outcome.set_actual("key", ans.key.to_string());
+        outcome.set_actual("size", ans.keysize);
+        outcome
+    }
 }
 
-/// challenge7 -- Decrypt AES 128 ECB
+/// Challenge7 -- Decrypt AES 128 ECB
 /// http://cryptopals.com/sets/1/challenges/7
 /// The Base64-encoded content in this file has been encrypted via AES-128 in ECB mode under the key
 /// "YELLOW SUBMARINE". (case-sensitive, without the quotes; exactly 16 characters; I like
@@ -318,24 +312,39 @@ pub fn challenge6() {
 /// Parameters: filename (&str) - Input File
 ///             key (&str) - String used to encrypt message
 /// Return: String - Secret message
-pub fn challenge7() {
-    // Definitions
-    let mut builder = Builder::new();
-    let challenge = builder
-        .header("Set 1, Challenge 7 - Decrypt AES-128-ECB given key")
-        .input_file("./input_files/set1challenge7.txt")
-        .input_type("base64")
-        .key("YELLOW SUBMARINE")
-        .key_type("ascii")
-        .build();
-
-    let contents = fs::read_to_string(challenge.get_input_file()).expect("Error: Unable to read file");
-    let mut input_storage = Storage::new_init(&contents.replace("\n", ""), challenge.get_input_type());
-    input_storage.change_base("ascii");
-    let key_storage = Storage::new_init(challenge.get_key(), challenge.get_key_type());
-
-    // Work
-    helper::inv_cipher_aes_128(&input_storage, &key_storage);
-
-    // Output
+pub struct Challenge7;
+
+impl Challenge for Challenge7 {
+    fn id(&self) -> u32 {
+        7
+    }
+
+    fn set(&self) -> u32 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Decrypt AES-128-ECB given key"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input_fixture("set1challenge7.txt", Encoding::Base64)
+            .key(Storage::new_init("YELLOW SUBMARINE", "ascii"))
+            .expect("answer", fixtures::read("set1challenge7_answer.txt"))
+            .overrides(overrides)
+            .build();
+
+        let input_storage = outcome.get_input();
+        let key_storage = outcome.get_key().expect("Error: AES needs a key");
+
+        // Work
+        let ans = aes::pkcs7_unpad(&aes::ecb_decrypt(&input_storage, key_storage), aes::BLOCK);
+
+        // Output
+        outcome.set_actual("answer", ans.to_string());
+        outcome
+    }
 }
@@ -0,0 +1,262 @@
+use storage::Storage;
+use cbc;
+use ecb;
+use profile;
+use challenge::{Builder, Challenge, Outcome, Overrides};
+
+use rand::thread_rng;
+
+/// challenges -- every implemented challenge in set 2 (see challenge::registry)
+/// Parameters: void
+/// Return: Vec<Box<dyn Challenge>> - set 2 challenges
+pub fn challenges() -> Vec<Box<dyn Challenge>> {
+    vec![
+        Box::new(Challenge11),
+        Box::new(Challenge12),
+        Box::new(Challenge13),
+        Box::new(Challenge14),
+        Box::new(Challenge16),
+    ]
+}
+
+/// Challenge11 -- An ECB/CBC detection oracle
+/// http://cryptopals.com/sets/2/challenges/11
+/// Encrypt the input under a random key w/ 5-10 random bytes before and after it, choosing ECB
+/// or CBC w/ a coin flip, and detect the mode from the ciphertext alone.  Every one of the
+/// TRIALS random oracles should be detected.
+/// input (&str) - chosen plaintext given to every oracle
+pub struct Challenge11;
+
+impl Challenge11 {
+    const TRIALS: usize = 1000;
+}
+
+impl Challenge for Challenge11 {
+    fn id(&self) -> u32 {
+        11
+    }
+
+    fn set(&self) -> u32 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "ECB/CBC detection oracle"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input(Storage::new_init_vec(&ecb::chosen_input(), "ascii"))
+            .expect("correct", Challenge11::TRIALS)
+            .overrides(overrides)
+            .build();
+
+        let mut input_storage = outcome.get_input();
+        if input_storage.get_data_type() != "ascii" {
+            input_storage.change_base("ascii");
+        }
+
+        // Work
+        let accuracy = ecb::measure_detection(input_storage.get_data(), Challenge11::TRIALS, &mut thread_rng());
+
+        // Output
+        outcome.set_actual("correct", accuracy.correct());
+        outcome.set_actual("ecb", format!("{}/{}", accuracy.ecb_correct, accuracy.ecb));
+        outcome.set_actual("cbc", format!("{}/{}", accuracy.cbc_correct, accuracy.cbc));
+        outcome
+    }
+}
+
+/// SECRET -- unknown string appended by the oracles of challenges 12 and 14
+const SECRET: &str = "Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkgaGFpciBjYW4gYmxvdwpUaGUg\
+                      Z2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBqdXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3Qg\
+                      ZHJvdmUgYnkK";
+
+/// SECRET_TEXT -- SECRET decoded
+const SECRET_TEXT: &str = "Rollin' in my 5.0\nWith my rag-top down so my hair can blow\n\
+                           The girlies on standby waving just to say hi\nDid you stop? No, I just drove by\n";
+
+/// byte_at_a_time -- helper function for challenges 12 and 14, attack an ECB oracle w/ a random
+/// key that appends the input of the outcome
+fn byte_at_a_time(outcome: &mut Outcome, max_prefix: usize) -> ecb::ByteAtATime {
+    let mut secret = outcome.get_input();
+    if secret.get_data_type() != "ascii" {
+        secret.change_base("ascii");
+    }
+    let oracle = ecb::AppendOracle::random(&mut thread_rng(), secret.get_data(), max_prefix);
+    let found = ecb::byte_at_a_time(&|input: &[u8]| oracle.encrypt(input));
+
+    outcome.set_actual("answer", String::from_utf8_lossy(&found.secret).into_owned());
+    outcome.set_actual("queries", found.queries);
+    found
+}
+
+/// Challenge12 -- Byte-at-a-time ECB decryption (Simple)
+/// http://cryptopals.com/sets/2/challenges/12
+/// An oracle encrypts your input followed by an unknown string under a random key in ECB mode.
+/// Find the block size, confirm ECB and decrypt the unknown string a byte at a time.
+/// input (&str) - the unknown string (base64)
+pub struct Challenge12;
+
+impl Challenge for Challenge12 {
+    fn id(&self) -> u32 {
+        12
+    }
+
+    fn set(&self) -> u32 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Byte-at-a-time ECB decryption (Simple)"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input(Storage::new_init(SECRET, "base64"))
+            .expect("answer", SECRET_TEXT)
+            .overrides(overrides)
+            .build();
+
+        // Work + Output
+        byte_at_a_time(&mut outcome, 0);
+        outcome
+    }
+}
+
+/// Challenge13 -- ECB cut-and-paste
+/// http://cryptopals.com/sets/2/challenges/13
+/// profile_for(email) encodes email=...&uid=10&role=user (quoting & and =) and encrypts it
+/// under a random key in ECB mode.  Using only profile_for, make a ciphertext that decrypts to a
+/// profile w/ role=admin.
+/// input (&str) - email of the forged admin
+pub struct Challenge13;
+
+impl Challenge for Challenge13 {
+    fn id(&self) -> u32 {
+        13
+    }
+
+    fn set(&self) -> u32 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "ECB cut-and-paste"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input(Storage::new_init("foo@bar.com", "ascii"))
+            .expect("role", "admin")
+            .overrides(overrides)
+            .build();
+
+        let mut email = outcome.get_input();
+        if email.get_data_type() != "ascii" {
+            email.change_base("ascii");
+        }
+
+        // Work
+        let oracle = ecb::ProfileOracle::random(&mut thread_rng(), true);
+        let forged = ecb::cut_and_paste(&|e: &str| oracle.encrypt(e), &email.to_string());
+        let pairs = oracle.decrypt(&forged);
+
+        // Output
+        outcome.set_actual("role", profile::get(&pairs, "role").unwrap_or(""));
+        outcome.set_actual("email", profile::get(&pairs, "email").unwrap_or(""));
+        outcome
+    }
+}
+
+/// Challenge14 -- Byte-at-a-time ECB decryption (Harder)
+/// http://cryptopals.com/sets/2/challenges/14
+/// Same as challenge 12, but the oracle also puts a random count of random bytes before your
+/// input.  Find the length of the prefix and pad it to a whole block first.
+/// input (&str) - the unknown string (base64)
+pub struct Challenge14;
+
+impl Challenge14 {
+    const MAX_PREFIX: usize = 64;
+}
+
+impl Challenge for Challenge14 {
+    fn id(&self) -> u32 {
+        14
+    }
+
+    fn set(&self) -> u32 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Byte-at-a-time ECB decryption (Harder)"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input(Storage::new_init(SECRET, "base64"))
+            .expect("answer", SECRET_TEXT)
+            .overrides(overrides)
+            .build();
+
+        // Work
+        let found = byte_at_a_time(&mut outcome, Challenge14::MAX_PREFIX);
+
+        // Output
+        outcome.set_actual("prefix", found.prefix_len);
+        outcome
+    }
+}
+
+/// Challenge16 -- CBC bitflipping attacks
+/// http://cryptopals.com/sets/2/challenges/16
+/// The user data is quoted and put between two comments, then encrypted under a random key in
+/// CBC mode.  Modify the ciphertext (w/o the key) so it decrypts w/ ;admin=true; in it.
+/// input (&str) - text to inject (at most 16 bytes)
+pub struct Challenge16;
+
+impl Challenge for Challenge16 {
+    fn id(&self) -> u32 {
+        16
+    }
+
+    fn set(&self) -> u32 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "CBC bitflipping attacks"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input(Storage::new_init(";admin=true;", "ascii"))
+            .expect("admin", "true")
+            .overrides(overrides)
+            .build();
+
+        let mut text = outcome.get_input();
+        if text.get_data_type() != "ascii" {
+            text.change_base("ascii");
+        }
+
+        // Work
+        let oracle = cbc::CommentOracle::random(&mut thread_rng());
+        let forged = cbc::inject(&|u: &str| oracle.encrypt(u), text.get_data());
+
+        // Output
+        outcome.set_actual("admin", oracle.is_admin(&forged).to_string());
+        outcome
+    }
+}
@@ -0,0 +1,84 @@
+use storage::{Encoding, Storage};
+use aes;
+use cbc;
+use fixtures;
+use challenge::{Builder, Challenge, Outcome, Overrides};
+
+use rand::thread_rng;
+
+/// challenges -- every implemented challenge in set 3 (see challenge::registry)
+/// Parameters: void
+/// Return: Vec<Box<dyn Challenge>> - set 3 challenges
+pub fn challenges() -> Vec<Box<dyn Challenge>> {
+    vec![Box::new(Challenge17)]
+}
+
+/// Challenge17 -- The CBC padding oracle
+/// http://cryptopals.com/sets/3/challenges/17
+/// Every line is encrypted under a random key and iv, and the oracle only says whether a
+/// ciphertext decrypts w/ valid padding.  Decrypt every line w/ the oracle, then also forge a
+/// ciphertext the oracle decrypts to FORGED.
+/// filename (&str) - one base64 string per line
+pub struct Challenge17;
+
+impl Challenge17 {
+    const FORGED: &'static str = "Forged w/ nothing but a padding oracle";
+}
+
+impl Challenge for Challenge17 {
+    fn id(&self) -> u32 {
+        17
+    }
+
+    fn set(&self) -> u32 {
+        3
+    }
+
+    fn title(&self) -> &'static str {
+        "The CBC padding oracle"
+    }
+
+    fn run_with(&self, overrides: &Overrides) -> Outcome {
+        // Definitions
+        let answer: Vec<String> = fixtures::read("set3challenge17.txt")
+            .lines()
+            .map(|l| {
+                let mut s = Storage::new_init(l, "base64");
+                s.change_base("ascii");
+                s.to_string()
+            })
+            .collect();
+        let mut outcome = Builder::new()
+            .header(&self.header())
+            .input_fixture("set3challenge17.txt", Encoding::Base64)
+            .expect("answer", answer.join("\n"))
+            .expect("forged", Challenge17::FORGED)
+            .overrides(overrides)
+            .build();
+
+        let oracle = cbc::PaddingOracle::random(&mut thread_rng());
+        let check = |iv: &[u8], cipher: &[u8]| oracle.padding_valid(iv, cipher);
+
+        // Work
+        let mut lines: Vec<String> = Vec::new();
+        let mut queries = 0;
+        for mut line in outcome.get_input_lines().into_iter().filter(|l| !l.is_empty()) {
+            if line.get_data_type() != "ascii" {
+                line.change_base("ascii");
+            }
+            let (iv, cipher) = oracle.encrypt(line.get_data());
+            let found = cbc::padding_oracle_decrypt(&check, &iv, &cipher);
+            let plain = aes::pkcs7_unpad(&Storage::new_init_vec(&found.plain, "ascii"), aes::BLOCK);
+            lines.push(plain.to_string());
+            queries += found.queries;
+        }
+        let forged = cbc::padding_oracle_encrypt(&check, Challenge17::FORGED.as_bytes(), aes::BLOCK);
+        let forged_plain = oracle.decrypt(&forged.iv, &forged.cipher).unwrap_or_default();
+
+        // Output
+        outcome.set_actual("answer", lines.join("\n"));
+        outcome.set_actual("forged", String::from_utf8_lossy(&forged_plain).into_owned());
+        outcome.set_actual("queries", queries + forged.queries);
+        outcome
+    }
+}
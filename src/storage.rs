@@ -1,8 +1,8 @@
+use std::fmt;
 use std::ops;
 
 // TODO: ownership? who owns what and why
 // TODO: clean up change base
-// TODO: implement display trait instead of to_string and print
 // TODO: only use Storage {} dec and only use self.data and self.data_type in here
 // TODO: consider to_owned() instead of to_string()
 // TODO: move out of set1
@@ -13,6 +13,40 @@ pub struct Storage {
     data_type: String,
 }
 
+/// Encoding -- data types a Storage can hold
+/// Ascii - raw bytes
+/// Hex - one hex digit per element
+/// Base64 - one base64 digit per element
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    Ascii,
+    Hex,
+    Base64,
+}
+
+impl Encoding {
+    /// parse -- encoding from its data type name
+    /// Parameters: data_type (&str) - ascii, hex or base64
+    /// Return: Encoding - matching encoding
+    pub fn parse(data_type: &str) -> Encoding {
+        match data_type {
+            "ascii" => Encoding::Ascii,
+            "hex" => Encoding::Hex,
+            "base64" => Encoding::Base64,
+            _ => panic!("Error: invalid type ({})", data_type),
+        }
+    }
+
+    /// as_str -- data type name used by Storage
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Encoding::Ascii => "ascii",
+            Encoding::Hex => "hex",
+            Encoding::Base64 => "base64",
+        }
+    }
+}
+
 impl Storage {
     /// new -- empty constructor for storage
     /// Parameters: void
@@ -41,7 +75,7 @@ impl Storage {
     /// Parameters: vec_inp (&vec) - input vector,
     ///             data_type (&str) - data type of input string (hex, base64, or ascii)
     /// Return: Storage (w/ data and data_type)
-    pub fn new_init_vec(vec_inp: &Vec<u8>, data_type: &str) -> Storage {
+    pub fn new_init_vec(vec_inp: &[u8], data_type: &str) -> Storage {
         Storage {
             data: vec_inp.to_vec(),
             data_type: data_type.to_owned(),
@@ -76,7 +110,7 @@ impl Storage {
     /// Parameters: str_inp (&str) - input string,
     ///             data_type (&str) - data type of input string (hex or base64)
     /// Return: void
-    pub fn set_data_vec(&mut self, vec_inp: &Vec<u8>, data_type: &str) {
+    pub fn set_data_vec(&mut self, vec_inp: &[u8], data_type: &str) {
         self.data = vec_inp.to_vec();
         self.data_type = data_type.to_string();
     }
@@ -102,6 +136,13 @@ impl Storage {
         self.data.len()
     }
 
+    /// is_empty -- helper function to get self.data.is_empty()
+    /// Parameters: void
+    /// Return: self.data.is_empty() (bool) - true if there is no data
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
     /// char_to_u8 -- helper function to convert (hex/base64) char to u8
     ///               (note: we don't want self here because we want to be able to use this
     ///               outside of this struct / want to use this in constructor)
@@ -115,17 +156,17 @@ impl Storage {
         if data_type == "hex" {
             // HEX
             match u {
-                48...57 => u - 48,  // 0 - 9
-                97...102 => u - 87, // a - f
-                65...70 => u - 55,  // A - F
+                48..=57 => u - 48,  // 0 - 9
+                97..=102 => u - 87, // a - f
+                65..=70 => u - 55,  // A - F
                 _ => panic!("Error: this is not a valid hex digit"),
             }
         } else if data_type == "base64" {
             // BASE64
             match u {
-                65...90 => u - 65,  // A - Z
-                97...122 => u - 71, // a - z
-                48...57 => u + 4,   // 0 - 9
+                65..=90 => u - 65,  // A - Z
+                97..=122 => u - 71, // a - z
+                48..=57 => u + 4,   // 0 - 9
                 43 => 62,           // +
                 47 => 63,           // /
                 61 => 255,          // = (padding character)
@@ -149,16 +190,16 @@ impl Storage {
         if data_type == "hex" {
             // HEX
             match u {
-                0...9 => (u + 48) as char,   // 0 - 9
-                10...15 => (u + 87) as char, // a - f
+                0..=9 => (u + 48) as char,   // 0 - 9
+                10..=15 => (u + 87) as char, // a - f
                 _ => panic!("Error: this is not a valid hex digit"),
             }
         } else if data_type == "base64" {
             // BASE64
             match u {
-                0...25 => (u + 65) as char,  // A - Z
-                26...51 => (u + 71) as char, // a - z
-                52...61 => (u - 4) as char,  // 0 - 9
+                0..=25 => (u + 65) as char,  // A - Z
+                26..=51 => (u + 71) as char, // a - z
+                52..=61 => (u - 4) as char,  // 0 - 9
                 62 => '+',                   // +
                 63 => '/',                   // /
                 255 => '=',                  // = (padding character)
@@ -174,24 +215,7 @@ impl Storage {
     /// Parameters: void 
     /// Return: void
     pub fn print(&self) {
-        println!("{}", self.to_string());
-    }
-
-    /// to_string -- helper function to convert self.data Vec<u8> to string
-    /// Parameters: void
-    /// Return: out (String) - Hex/Base64/Ascii data in string format
-    pub fn to_string(&self) -> String {
-        self.data
-            .iter()
-            .map(|i| Storage::u8_to_char(*i, self.data_type.as_str()))
-            .collect()
-    }
-
-    /// to_str -- helper function to convert self.data Vec<u8> to &str
-    /// Parameters: void
-    /// Return: out (String) - Hex/Base64/Ascii data in &str format
-    pub fn to_str(&self) -> &str {
-        &self.to_string()
+        println!("{}", self);
     }
 
     /// change_base -- convert old_base to new_init_base
@@ -213,7 +237,7 @@ impl Storage {
 
                 // if converting to base64, we add elements to the vec by 3 hex values
                 // 00001111, 00001122, 00002222 -> 00111111, 00222222
-                if new_init_base == "base64" && self.data.len() % 3 != 0 {
+                if new_init_base == "base64" && !self.data.len().is_multiple_of(3) {
                     panic!("Error: hex input does not fit nicely into base64.");
                 }
 
@@ -242,7 +266,7 @@ impl Storage {
 
                 // if converting to hex, we add elements to the vec by 2 base64 values
                 // 00111122 00223333 -> 00001111, 00002222, 00003333
-                if new_init_base == "hex" && self.data.len() % 2 != 0 {
+                if new_init_base == "hex" && !self.data.len().is_multiple_of(2) {
                     panic!("Error: base64 doesn't fit nicely into hex.");
                 }
 
@@ -268,7 +292,7 @@ impl Storage {
                 }
             } else if self.data_type == "hex" && new_init_base == "ascii" {
                 // hex -> ascii
-                if self.data.len() % 2 != 0 {
+                if !self.data.len().is_multiple_of(2) {
                     panic!("Error: hex doesn't fit nicely into an ASCII string");
                 }
 
@@ -295,7 +319,7 @@ impl Storage {
                 }
             } else if self.data_type == "base64" && new_init_base == "ascii" {
                 // base64 -> ascii
-                if self.data.len() % 4 != 0 {
+                if !self.data.len().is_multiple_of(4) {
                     panic!(
                         "Error: base64 doesn't fit nicely into an ASCII string. \
                          Length of base64 is {}",
@@ -423,6 +447,24 @@ impl Storage {
     }
 }
 
+impl Default for Storage {
+    fn default() -> Storage {
+        Storage::new()
+    }
+}
+
+/// Display -- Hex/Base64/Ascii data in string format
+impl fmt::Display for Storage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let out: String = self
+            .data
+            .iter()
+            .map(|i| Storage::u8_to_char(*i, self.data_type.as_str()))
+            .collect();
+        f.write_str(&out)
+    }
+}
+
 /// XOR implementation for Storage ^ Storage = Storage
 /// handles repeating XOR so if lhs is bigger than rhs
 ///  it will repeatable XOR the rhs on the lhs
@@ -432,7 +474,7 @@ impl<'a> ops::BitXor<&'a Storage> for &'a Storage {
     type Output = Storage;
 
     fn bitxor(self, rhs: &Storage) -> Storage {
-        if self.data_type == "" || rhs.data_type == "" {
+        if self.data_type.is_empty() || rhs.data_type.is_empty() {
             panic!("Error: cannot XOR on empty storage");
         }
 
@@ -944,7 +986,7 @@ mod tests {
     fn check_invalid_xor_empty_storages() {
         let lhs: Storage = Storage::new();
         let rhs: Storage = Storage::new();
-        &lhs ^ &rhs;
+        let _ = &lhs ^ &rhs;
     }
 
     #[test]
@@ -952,7 +994,7 @@ mod tests {
     fn check_invalid_xor_empty_and_full() {
         let lhs = Storage::new_init("abc", "ascii");
         let rhs = Storage::new();
-        &lhs ^ &rhs;
+        let _ = &lhs ^ &rhs;
     }
 
     #[test]
@@ -960,14 +1002,14 @@ mod tests {
     fn check_invalid_xor_full_and_empty() {
         let lhs = Storage::new();
         let rhs = Storage::new_init("abc", "ascii");
-        &lhs ^ &rhs;
+        let _ = &lhs ^ &rhs;
     }
     #[test]
     #[should_panic]
     fn check_invalid_xor_different_types() {
         let lhs = Storage::new_init("abc", "ascii");
         let rhs = Storage::new_init("abc", "hex");
-        &lhs ^ &rhs;
+        let _ = &lhs ^ &rhs;
     }
 
     #[test]
@@ -975,6 +1017,6 @@ mod tests {
     fn check_invalid_xor_right_side_bigger() {
         let lhs = Storage::new_init("abc", "hex");
         let rhs = Storage::new_init("01234abcd", "hex");
-        &lhs ^ &rhs;
+        let _ = &lhs ^ &rhs;
     }
 }
@@ -0,0 +1,1061 @@
+//! attacks on single-byte and repeating-key XOR
+
+use analysis;
+use keystream::Keystream;
+use set1::helper;
+use storage::Storage;
+
+use std::cmp::Ordering;
+use std::collections::HashMap; // hashmap used for the char freq table
+use std::thread;
+
+/// KeySizeMethod -- statistic used to guess the key size of repeating-key XOR
+/// HammingDistance - smallest normalised hamming distance averaged over every pair of blocks
+/// IndexOfCoincidence - largest average index of coincidence of the transposed columns
+/// Autocorrelation - largest fraction of bytes that match the byte keysize positions later
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeySizeMethod {
+    HammingDistance,
+    IndexOfCoincidence,
+    Autocorrelation,
+}
+
+/// SingleByteXor -- best guess for a single-byte XOR key
+/// key (u8) - key byte
+/// plaintext (Storage) - ciphertext XOR'd against the key (ascii)
+/// score (f32) - english score of the plaintext
+pub struct SingleByteXor {
+    pub key: u8,
+    pub plaintext: Storage,
+    pub score: f32,
+}
+
+/// DetectedLine -- line of a file that decrypts to english under a single-byte XOR key
+/// line (usize) - line number in the file (starting at 1)
+/// key (u8) - key byte
+/// plaintext (Storage) - line XOR'd against the key (ascii)
+/// score (f32) - english score of the plaintext per byte
+pub struct DetectedLine {
+    pub line: usize,
+    pub key: u8,
+    pub plaintext: Storage,
+    pub score: f32,
+}
+
+/// RepeatingKeyXor -- best guess for a repeating-key XOR key
+/// key (Storage) - recovered key (ascii)
+/// plaintext (Storage) - ciphertext XOR'd against the key (ascii)
+/// keysize (usize) - length of the key
+/// score (f32) - english score of the plaintext per byte, less ln(256) per key byte
+/// confidence (f64) - fraction of the plaintext that is printable ascii (0.0 - 1.0)
+/// keysizes (Vec<(usize, f64)>) - top key sizes that were tried (best first) w/ their statistic
+pub struct RepeatingKeyXor {
+    pub key: Storage,
+    pub plaintext: Storage,
+    pub keysize: usize,
+    pub score: f32,
+    pub confidence: f64,
+    pub keysizes: Vec<(usize, f64)>,
+}
+
+/// ManyTimePad -- best guess for a keystream reused across several ciphertexts
+/// keystream (Storage) - recovered keystream as long as the longest ciphertext (ascii)
+/// plaintexts (Vec<Storage>) - every ciphertext XOR'd against the keystream (ascii)
+/// scores (Vec<f32>) - english score per byte of each keystream position
+/// samples (Vec<usize>) - number of ciphertexts that cover each keystream position
+pub struct ManyTimePad {
+    pub keystream: Storage,
+    pub plaintexts: Vec<Storage>,
+    pub scores: Vec<f32>,
+    pub samples: Vec<usize>,
+}
+
+/// KnownPlaintext -- fragment of plaintext known to be in a ciphertext
+/// plaintext (Storage) - known bytes (any data type)
+/// offset (Option<usize>) - position of the fragment, None when it has to be searched for
+pub struct KnownPlaintext {
+    pub plaintext: Storage,
+    pub offset: Option<usize>,
+}
+
+/// KnownPlaintextKey -- key material recovered from known plaintext
+/// keystream (Keystream) - keystream implied by the fragments (and the key when found)
+/// period (Option<usize>) - length of the repeating key when the keystream repeats
+/// key (Option<Storage>) - repeating key when every byte of it is known (ascii)
+/// offsets (Vec<Option<usize>>) - position of each fragment, None when it wasn't found
+pub struct KnownPlaintextKey {
+    pub keystream: Keystream,
+    pub period: Option<usize>,
+    pub key: Option<Storage>,
+    pub offsets: Vec<Option<usize>>,
+}
+
+/// to_ascii -- helper function to copy a storage into raw bytes (ascii)
+/// Parameters: s (&Storage) - storage in any data type
+/// Return: Storage - copy of s w/ data type ascii
+fn to_ascii(s: &Storage) -> Storage {
+    let mut out = Storage::new_init_vec(s.get_data(), s.get_data_type());
+    out.change_base("ascii");
+    out
+}
+
+/// english_score -- log-likelihood of the plaintext under the character frequency table.
+/// Summing raw frequencies lets a few spaces outweigh a correct decryption of a short
+/// message, the log punishes every unlikely byte instead.
+/// Parameters: s (&Storage) - plaintext (ascii)
+///             freq (&HashMap<u8, f32>) - character frequency table
+/// Return: f32 - score, higher (closer to 0) is more english looking
+pub fn english_score(s: &Storage, freq: &HashMap<u8, f32>) -> f32 {
+    let total: f32 = freq.values().sum();
+    let unknown = (1e-6f32).ln();
+    s.get_data()
+        .iter()
+        .map(|d| match freq.get(d) {
+            Some(f) if *f > 0f32 => (f / total).ln(),
+            _ => unknown,
+        })
+        .sum()
+}
+
+/// rank_single_byte -- try all 256 single-byte XOR keys and rank them by how english
+/// looking the plaintext is
+/// Parameters: cipher (&Storage) - encrypted message (any data type)
+///             freq (&HashMap<u8, f32>) - character frequency table
+/// Return: Vec<SingleByteXor> - every key, best score first (ties go to the smaller key)
+pub fn rank_single_byte(cipher: &Storage, freq: &HashMap<u8, f32>) -> Vec<SingleByteXor> {
+    let cipher = to_ascii(cipher);
+    let mut out: Vec<SingleByteXor> = (0u8..=255u8)
+        .map(|key| {
+            let key_obj = Storage::new_init_vec(&[key], "ascii");
+            let ans = &cipher ^ &key_obj;
+            SingleByteXor {
+                key,
+                score: english_score(&ans, freq),
+                plaintext: ans,
+            }
+        })
+        .collect();
+
+    // stable sort keeps the smaller key first on a tie
+    out.sort_by(|l, r| r.score.partial_cmp(&l.score).unwrap_or(Ordering::Equal));
+    out
+}
+
+/// break_single_byte -- find the single-byte XOR key that produces the most english looking
+/// plaintext by trying all 256 keys
+/// Parameters: cipher (&Storage) - encrypted message (any data type)
+///             freq (&HashMap<u8, f32>) - character frequency table
+/// Return: SingleByteXor - best key, plaintext and score
+pub fn break_single_byte(cipher: &Storage, freq: &HashMap<u8, f32>) -> SingleByteXor {
+    rank_single_byte(cipher, freq).remove(0)
+}
+
+/// detect_single_byte -- find every line that was encrypted w/ single-byte XOR.  Each line
+/// is broken w/ break_single_byte and kept if its score per byte reaches the threshold.
+/// The lines are split between threads so large files use every core.
+/// Parameters: lines (&[Storage]) - one ciphertext per line (any data type)
+///             threshold (f32) - minimum english score per byte (english is around -3.4,
+///                               random bytes fall below -5.0)
+///             threads (usize) - number of worker threads (0 uses every core)
+/// Return: Vec<DetectedLine> - lines above the threshold, best score first
+pub fn detect_single_byte(lines: &[Storage], threshold: f32, threads: usize) -> Vec<DetectedLine> {
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    let chunk = lines.len().div_ceil(threads).max(1);
+    let freq = helper::freq::get_char_freq_table();
+
+    let mut out: Vec<DetectedLine> = thread::scope(|scope| {
+        let workers: Vec<_> = lines
+            .chunks(chunk)
+            .enumerate()
+            .map(|(c, part)| {
+                let freq = &freq;
+                scope.spawn(move || {
+                    part.iter()
+                        .enumerate()
+                        .filter(|(_, l)| !l.is_empty())
+                        .map(|(i, l)| {
+                            let ans = break_single_byte(l, freq);
+                            DetectedLine {
+                                line: c * chunk + i + 1,
+                                key: ans.key,
+                                score: ans.score / ans.plaintext.len() as f32,
+                                plaintext: ans.plaintext,
+                            }
+                        })
+                        .filter(|d| d.score >= threshold)
+                        .collect::<Vec<DetectedLine>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().expect("Error: detection thread panicked"))
+            .collect()
+    });
+    out.sort_by(|l, r| {
+        r.score
+            .partial_cmp(&l.score)
+            .unwrap_or(Ordering::Equal)
+            .then(l.line.cmp(&r.line))
+    });
+    out
+}
+
+/// keysize_hamming_distance -- normalised hamming distance averaged over every pair of
+/// keysize blocks (lower is better)
+/// Parameters: data (&[u8]) - ciphertext bytes
+///             keysize (usize) - guessed key size
+/// Return: f64 - average hamming distance per byte
+fn keysize_hamming_distance(data: &[u8], keysize: usize) -> f64 {
+    let blocks: Vec<&[u8]> = data.chunks(keysize).filter(|b| b.len() == keysize).collect();
+    let mut total: u64 = 0;
+    let mut pairs: u64 = 0;
+
+    for i in 0..blocks.len() {
+        for j in (i + 1)..blocks.len() {
+            total += blocks[i]
+                .iter()
+                .zip(blocks[j].iter())
+                .map(|(l, r)| (l ^ r).count_ones() as u64)
+                .sum::<u64>();
+            pairs += 1;
+        }
+    }
+
+    if pairs == 0 {
+        return f64::INFINITY;
+    }
+    total as f64 / pairs as f64 / keysize as f64
+}
+
+/// keysize_autocorrelation -- fraction of bytes equal to the byte keysize positions later
+/// (higher is better)
+/// Parameters: data (&[u8]) - ciphertext bytes
+///             keysize (usize) - guessed key size
+/// Return: f64 - fraction of matching bytes
+fn keysize_autocorrelation(data: &[u8], keysize: usize) -> f64 {
+    if data.len() <= keysize {
+        return 0.0;
+    }
+    let matches = data
+        .iter()
+        .zip(data.iter().skip(keysize))
+        .filter(|(l, r)| l == r)
+        .count();
+    matches as f64 / (data.len() - keysize) as f64
+}
+
+/// estimate_keysize -- rank every key size between min and max using a statistic
+/// Parameters: cipher (&Storage) - encrypted message (any data type)
+///             min (usize) - smallest key size to try
+///             max (usize) - largest key size to try (included)
+///             method (KeySizeMethod) - statistic used to rank the key sizes
+/// Return: Vec<(usize, f64)> - (key size, statistic) sorted from most to least likely
+pub fn estimate_keysize(
+    cipher: &Storage,
+    min: usize,
+    max: usize,
+    method: KeySizeMethod,
+) -> Vec<(usize, f64)> {
+    if min == 0 || min > max {
+        panic!("Error: invalid key size range {} to {}", min, max);
+    }
+
+    let cipher = to_ascii(cipher);
+    let data = cipher.get_data();
+    let mut out: Vec<(usize, f64)> = (min..=max)
+        .map(|k| match method {
+            KeySizeMethod::HammingDistance => (k, keysize_hamming_distance(data, k)),
+            KeySizeMethod::IndexOfCoincidence => {
+                (k, analysis::period_index_of_coincidence(&cipher, k))
+            }
+            KeySizeMethod::Autocorrelation => (k, keysize_autocorrelation(data, k)),
+        })
+        .collect();
+
+    // hamming distance ranks lowest first, the others rank highest first
+    // ties go to the smaller key size since multiples of the key size look just as good
+    out.sort_by(|l, r| {
+        let order = match method {
+            KeySizeMethod::HammingDistance => l.1.partial_cmp(&r.1),
+            _ => r.1.partial_cmp(&l.1),
+        };
+        order.unwrap_or(Ordering::Equal).then(l.0.cmp(&r.0))
+    });
+    out
+}
+
+/// printable_fraction -- fraction of bytes that are printable ascii or whitespace
+/// Parameters: s (&Storage) - plaintext (ascii)
+/// Return: f64 - fraction between 0.0 and 1.0
+fn printable_fraction(s: &Storage) -> f64 {
+    if s.is_empty() {
+        return 0.0;
+    }
+    let printable = s
+        .get_data()
+        .iter()
+        .filter(|d| (0x20..0x7F).contains(*d) || **d == b'\n' || **d == b'\r' || **d == b'\t')
+        .count();
+    printable as f64 / s.len() as f64
+}
+
+/// break_repeating_key_with_size -- solve repeating-key XOR when the key size is known
+/// transposes the ciphertext and solves every column as single-byte XOR
+/// Parameters: cipher (&Storage) - encrypted message (any data type)
+///             keysize (usize) - length of the key
+///             freq (&HashMap<u8, f32>) - character frequency table
+/// Return: (Storage, Storage) - (key, plaintext) both ascii
+pub fn break_repeating_key_with_size(
+    cipher: &Storage,
+    keysize: usize,
+    freq: &HashMap<u8, f32>,
+) -> (Storage, Storage) {
+    let cipher = to_ascii(cipher);
+    let key: Vec<u8> = helper::split_into_blocks(&cipher, keysize)
+        .iter()
+        .map(|block| break_single_byte(block, freq).key)
+        .collect();
+
+    let key_obj = Storage::new_init_vec(&key, "ascii");
+    let plaintext = &cipher ^ &key_obj;
+    (key_obj, plaintext)
+}
+
+/// MIN_COLUMN -- fewest ciphertext bytes per key byte worth solving.  A column of one or two
+/// bytes always has some key byte that turns it into common letters.
+const MIN_COLUMN: usize = 3;
+
+/// key_period -- length of the shortest key that repeats to make this key
+/// Parameters: key (&[u8]) - key bytes
+/// Return: usize - period (key.len() when the key doesn't repeat)
+fn key_period(key: &[u8]) -> usize {
+    (1..key.len())
+        .filter(|p| key.len().is_multiple_of(*p))
+        .find(|&p| key.iter().enumerate().all(|(i, k)| *k == key[i % p]))
+        .unwrap_or(key.len())
+}
+
+/// rank_repeating_key -- break repeating-key XOR once for each likely key size
+/// Step 1: rank key sizes between min and max w/ the chosen statistic (key sizes that leave
+///         fewer than MIN_COLUMN bytes per column are skipped)
+/// Step 2: for the top_k key sizes, solve each transposed column as single-byte XOR
+/// Step 3: collapse keys that repeat a shorter key to their period
+/// Step 4: rank the (key, plaintext) pairs by english score per byte, less ln(256) per key
+///         byte so a longer key has to explain the plaintext better than the shorter one
+/// Parameters: cipher (&Storage) - encrypted message (any data type)
+///             min (usize) - smallest key size to try
+///             max (usize) - largest key size to try (included)
+///             top_k (usize) - number of key sizes to solve
+///             method (KeySizeMethod) - statistic used to rank the key sizes
+/// Return: Vec<RepeatingKeyXor> - one candidate per distinct key, best score first
+///         (ties go to the smaller key size)
+pub fn rank_repeating_key(
+    cipher: &Storage,
+    min: usize,
+    max: usize,
+    top_k: usize,
+    method: KeySizeMethod,
+) -> Vec<RepeatingKeyXor> {
+    if cipher.is_empty() {
+        panic!("Error: cannot break an empty ciphertext");
+    }
+
+    let freq = helper::freq::get_char_freq_table();
+    let max = max.min((cipher.len() / MIN_COLUMN).max(min));
+    let mut keysizes = estimate_keysize(cipher, min, max, method);
+    keysizes.truncate(top_k.max(1));
+
+    let key_byte_cost = (256f32).ln();
+    let mut out: Vec<RepeatingKeyXor> = keysizes
+        .iter()
+        .map(|&(keysize, _)| {
+            let (key, plaintext) = break_repeating_key_with_size(cipher, keysize, &freq);
+            let period = key_period(key.get_data());
+            let penalty = period as f32 * key_byte_cost;
+            RepeatingKeyXor {
+                score: (english_score(&plaintext, &freq) - penalty) / plaintext.len() as f32,
+                confidence: printable_fraction(&plaintext),
+                key: key.index(0, period),
+                plaintext,
+                keysize: period,
+                keysizes: keysizes.clone(),
+            }
+        })
+        .collect();
+    out.sort_by(|l, r| {
+        r.score
+            .partial_cmp(&l.score)
+            .unwrap_or(Ordering::Equal)
+            .then(l.keysize.cmp(&r.keysize))
+    });
+
+    // multiples of the key size collapse to the same key, keep the first
+    let mut seen: Vec<Vec<u8>> = Vec::new();
+    out.retain(|c| {
+        let fresh = !seen.contains(c.key.get_data());
+        if fresh {
+            seen.push(c.key.get_data().clone());
+        }
+        fresh
+    });
+    out
+}
+
+/// break_repeating_key -- break repeating-key XOR (best candidate of rank_repeating_key)
+/// Parameters: cipher (&Storage) - encrypted message (any data type)
+///             min (usize) - smallest key size to try
+///             max (usize) - largest key size to try (included)
+///             top_k (usize) - number of key sizes to solve
+///             method (KeySizeMethod) - statistic used to rank the key sizes
+/// Return: RepeatingKeyXor - best key, plaintext, key size and confidence
+pub fn break_repeating_key(
+    cipher: &Storage,
+    min: usize,
+    max: usize,
+    top_k: usize,
+    method: KeySizeMethod,
+) -> RepeatingKeyXor {
+    rank_repeating_key(cipher, min, max, top_k, method).remove(0)
+}
+
+/// xor_repeating_key -- XOR a message against a repeating key of any length
+/// unlike Storage ^ Storage a key longer than the message is fine, only its start is used
+/// Parameters: message (&Storage) - message (any data type)
+///             key (&Storage) - key (any data type)
+/// Return: Storage - message XOR'd against the key (ascii)
+pub fn xor_repeating_key(message: &Storage, key: &Storage) -> Storage {
+    let key = to_ascii(key);
+    if key.is_empty() {
+        panic!("Error: cannot XOR against an empty key");
+    }
+    let out: Vec<u8> = to_ascii(message)
+        .get_data()
+        .iter()
+        .enumerate()
+        .map(|(i, b)| b ^ key.get_data()[i % key.len()])
+        .collect();
+    Storage::new_init_vec(&out, "ascii")
+}
+
+/// xor_keystream -- XOR a message against the start of a keystream
+/// unlike Storage ^ Storage the keystream does not repeat, it is cut to the message length
+/// Parameters: message (&Storage) - message (ascii)
+///             keystream (&Storage) - keystream at least as long as the message (ascii)
+/// Return: Storage - message XOR'd against the keystream (ascii)
+pub fn xor_keystream(message: &Storage, keystream: &Storage) -> Storage {
+    if message.is_empty() {
+        return Storage::new_init_vec(&Vec::new(), "ascii");
+    }
+    &to_ascii(message) ^ &keystream.index(0, message.len())
+}
+
+/// pin_plaintext -- pin the keystream bytes implied by a known piece of plaintext
+/// Parameters: pins (&mut HashMap<usize, u8>) - keystream position -> keystream byte
+///             cipher (&Storage) - ciphertext the plaintext belongs to (any data type)
+///             offset (usize) - position of the plaintext in the ciphertext
+///             plaintext (&Storage) - known plaintext (any data type)
+/// Return: void
+pub fn pin_plaintext(
+    pins: &mut HashMap<usize, u8>,
+    cipher: &Storage,
+    offset: usize,
+    plaintext: &Storage,
+) {
+    let cipher = to_ascii(cipher);
+    let plaintext = to_ascii(plaintext);
+    if offset + plaintext.len() > cipher.len() {
+        panic!(
+            "Error: plaintext does not fit in the ciphertext. Offset is {}, plaintext length \
+             is {}, ciphertext length is {}",
+            offset,
+            plaintext.len(),
+            cipher.len()
+        );
+    }
+
+    for (i, p) in plaintext.get_data().iter().enumerate() {
+        pins.insert(offset + i, cipher.get_data()[offset + i] ^ p);
+    }
+}
+
+/// break_many_time_pad -- recover a keystream that was reused across several ciphertexts
+/// (fixed nonce CTR, reused one-time pad).  Every keystream position is solved as
+/// single-byte XOR over the bytes of every ciphertext at that position.
+/// Parameters: ciphertexts (&[Storage]) - encrypted messages, lengths may differ (any data type)
+/// Return: ManyTimePad - keystream, plaintexts and per position scores
+pub fn break_many_time_pad(ciphertexts: &[Storage]) -> ManyTimePad {
+    break_many_time_pad_pinned(ciphertexts, &HashMap::new())
+}
+
+/// break_many_time_pad_pinned -- break_many_time_pad where some keystream bytes are already
+/// known.  Pinned positions are not guessed, use pin_plaintext to build the pins.
+/// Parameters: ciphertexts (&[Storage]) - encrypted messages, lengths may differ (any data type)
+///             pins (&HashMap<usize, u8>) - keystream position -> keystream byte
+/// Return: ManyTimePad - keystream, plaintexts and per position scores
+pub fn break_many_time_pad_pinned(
+    ciphertexts: &[Storage],
+    pins: &HashMap<usize, u8>,
+) -> ManyTimePad {
+    let ciphertexts: Vec<Storage> = ciphertexts.iter().map(to_ascii).collect();
+    let length = ciphertexts.iter().map(|c| c.len()).max().unwrap_or(0);
+    let freq = helper::freq::get_char_freq_table();
+
+    let mut keystream: Vec<u8> = Vec::new();
+    let mut scores: Vec<f32> = Vec::new();
+    let mut samples: Vec<usize> = Vec::new();
+
+    for i in 0..length {
+        // transpose -- every ciphertext that is long enough adds a byte to the column
+        let column: Vec<u8> = ciphertexts
+            .iter()
+            .filter(|c| c.len() > i)
+            .map(|c| c.get_data()[i])
+            .collect();
+        let column = Storage::new_init_vec(&column, "ascii");
+
+        let (key, score) = match pins.get(&i) {
+            Some(k) => {
+                let key_obj = Storage::new_init_vec(&[*k], "ascii");
+                let ans = &column ^ &key_obj;
+                (*k, english_score(&ans, &freq))
+            }
+            None => {
+                let ans = break_single_byte(&column, &freq);
+                (ans.key, ans.score)
+            }
+        };
+
+        keystream.push(key);
+        scores.push(score / column.len() as f32);
+        samples.push(column.len());
+    }
+
+    let keystream = Storage::new_init_vec(&keystream, "ascii");
+    ManyTimePad {
+        plaintexts: ciphertexts.iter().map(|c| xor_keystream(c, &keystream)).collect(),
+        keystream,
+        scores,
+        samples,
+    }
+}
+
+/// MIN_EVIDENCE -- bytes that must confirm a fragment placed at an unknown offset
+const MIN_EVIDENCE: usize = 4;
+
+/// repeating_period -- smallest period the known bytes are consistent w/
+/// a period only counts if at least MIN_EVIDENCE known bytes fall on a key position that
+/// is already known, otherwise every period longer than the known bytes would be accepted
+/// and a single coincidental match would be enough for a short one
+/// Parameters: bytes (&[Option<u8>]) - keystream byte at each position, None when unknown
+///             max (usize) - largest period to try (included)
+/// Return: Option<usize> - period, None when no period fits
+fn repeating_period(bytes: &[Option<u8>], max: usize) -> Option<usize> {
+    for period in 1..=max {
+        let mut key: Vec<Option<u8>> = vec![None; period];
+        let mut overlap = 0;
+        let mut consistent = true;
+
+        for (i, b) in bytes.iter().enumerate() {
+            if let Some(b) = b {
+                match key[i % period] {
+                    Some(k) if k != *b => {
+                        consistent = false;
+                        break;
+                    }
+                    Some(_) => overlap += 1,
+                    None => key[i % period] = Some(*b),
+                }
+            }
+        }
+
+        if consistent && overlap >= MIN_EVIDENCE {
+            return Some(period);
+        }
+    }
+    None
+}
+
+/// detect_period -- smallest period of a partially known keystream (see repeating_period)
+/// Parameters: keystream (&Keystream) - partially known keystream
+///             max (usize) - largest period to try (included)
+/// Return: Option<usize> - period, None when no period fits
+pub fn detect_period(keystream: &Keystream, max: usize) -> Option<usize> {
+    repeating_period(keystream.get_bytes(), max)
+}
+
+/// confirmed_bytes -- count the bytes of a keystream segment that the known keystream (or
+/// the repeating key when the period is known) confirms
+/// Parameters: keystream (&Keystream) - partially known keystream
+///             period (Option<usize>) - length of the repeating key
+///             offset (usize) - position of the segment
+///             segment (&Storage) - keystream segment (ascii)
+/// Return: Option<usize> - number of confirmed bytes, None if any known byte disagrees
+fn confirmed_bytes(
+    keystream: &Keystream,
+    period: Option<usize>,
+    offset: usize,
+    segment: &Storage,
+) -> Option<usize> {
+    let mut confirmed = 0;
+    for (i, b) in segment.get_data().iter().enumerate() {
+        let known: Vec<u8> = match period {
+            Some(p) => (((offset + i) % p)..keystream.len())
+                .step_by(p)
+                .filter_map(|j| keystream.get(j))
+                .collect(),
+            None => keystream.get(offset + i).into_iter().collect(),
+        };
+        if known.iter().any(|k| k != b) {
+            return None;
+        }
+        if !known.is_empty() {
+            confirmed += 1;
+        }
+    }
+    Some(confirmed)
+}
+
+/// recover_known_plaintext -- recover XOR key material from known pieces of plaintext
+/// Step 1: fragments at a known offset reveal keystream bytes (ciphertext ^ plaintext)
+/// Step 2: fragments at an unknown offset are tried at every offset, an offset is accepted
+///         when the implied keystream repeats on its own or agrees w/ the known keystream
+/// Step 3: the period of the keystream gives the repeating key, the key fills in the rest
+///         of the keystream
+/// Parameters: cipher (&Storage) - encrypted message (any data type)
+///             fragments (&[KnownPlaintext]) - known plaintext
+///             max_period (usize) - longest repeating key to look for
+/// Return: KnownPlaintextKey - keystream, period, key and fragment offsets
+pub fn recover_known_plaintext(
+    cipher: &Storage,
+    fragments: &[KnownPlaintext],
+    max_period: usize,
+) -> KnownPlaintextKey {
+    let cipher = to_ascii(cipher);
+    let fragments: Vec<(Storage, Option<usize>)> = fragments
+        .iter()
+        .map(|f| (to_ascii(&f.plaintext), f.offset))
+        .collect();
+    let mut keystream = Keystream::new(cipher.len());
+    let mut offsets: Vec<Option<usize>> = vec![None; fragments.len()];
+
+    // Step 1 - fragments w/ a known offset
+    for (i, (plaintext, offset)) in fragments.iter().enumerate() {
+        if let Some(offset) = offset {
+            keystream.commit(&cipher, *offset, plaintext);
+            offsets[i] = Some(*offset);
+        }
+    }
+
+    // Step 2 - search for fragments w/o an offset, longest first since they are the most
+    // likely to repeat on their own
+    let mut order: Vec<usize> = (0..fragments.len()).filter(|i| fragments[*i].1.is_none()).collect();
+    order.sort_by(|l, r| fragments[*r].0.len().cmp(&fragments[*l].0.len()));
+    for i in order {
+        let plaintext = &fragments[i].0;
+        if plaintext.is_empty() || plaintext.len() > cipher.len() {
+            continue;
+        }
+        let period = detect_period(&keystream, max_period);
+
+        let mut best: Option<(usize, usize)> = None; // (offset, evidence)
+        for offset in 0..=(cipher.len() - plaintext.len()) {
+            let segment = &cipher.index(offset, offset + plaintext.len()) ^ plaintext;
+
+            // evidence - bytes confirmed by the known keystream, otherwise the number of bytes
+            // that repeat inside the segment itself
+            let evidence = match confirmed_bytes(&keystream, period, offset, &segment) {
+                None => continue,
+                Some(c) if c > 0 => c,
+                Some(_) => {
+                    let bytes: Vec<Option<u8>> =
+                        segment.get_data().iter().map(|b| Some(*b)).collect();
+                    match repeating_period(&bytes, max_period) {
+                        Some(p) => plaintext.len() - p,
+                        None => 0,
+                    }
+                }
+            };
+
+            // a handful of matching bytes happen by chance when trying every offset
+            if evidence >= MIN_EVIDENCE && best.is_none_or(|b| evidence > b.1) {
+                best = Some((offset, evidence));
+            }
+        }
+
+        if let Some((offset, _)) = best {
+            keystream.commit(&cipher, offset, plaintext);
+            offsets[i] = Some(offset);
+        }
+    }
+
+    // Step 3 - repeating key
+    let period = detect_period(&keystream, max_period);
+    let mut key: Option<Storage> = None;
+    if let Some(p) = period {
+        let mut partial: Vec<Option<u8>> = vec![None; p];
+        for (i, b) in keystream.get_bytes().iter().enumerate() {
+            if b.is_some() {
+                partial[i % p] = *b;
+            }
+        }
+        for i in 0..keystream.len() {
+            if keystream.get(i).is_none() {
+                keystream.set(i, partial[i % p]);
+            }
+        }
+        if partial.iter().all(|b| b.is_some()) {
+            let k: Vec<u8> = partial.iter().map(|b| b.unwrap()).collect();
+            key = Some(Storage::new_init_vec(&k, "ascii"));
+        }
+    }
+
+    KnownPlaintextKey {
+        keystream,
+        period,
+        key,
+        offsets,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures;
+
+    const PLAINTEXT: &str = "It was the best of times, it was the worst of times, it was the age \
+        of wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch \
+        of incredulity, it was the season of Light, it was the season of Darkness, it was the \
+        spring of hope, it was the winter of despair.";
+
+    fn encrypt(plaintext: &str, key: &str) -> Storage {
+        &Storage::new_init(plaintext, "ascii") ^ &Storage::new_init(key, "ascii")
+    }
+
+    const LINES: [&str; 12] = [
+        "We had everything before us, we had nothing before us,",
+        "we were all going direct to Heaven,",
+        "we were all going direct the other way.",
+        "In short, the period was so far like the present period,",
+        "that some of its noisiest authorities insisted on its being received,",
+        "for good or for evil, in the superlative degree of comparison only.",
+        "There were a king with a large jaw and a queen with a plain face,",
+        "on the throne of England;",
+        "there were a king with a large jaw and a queen with a fair face,",
+        "on the throne of France.",
+        "In both countries it was clearer than crystal to the lords of the State",
+        "preserves of loaves and fishes, that things in general were settled for ever.",
+    ];
+
+    fn keystream(length: usize) -> Storage {
+        let k: Vec<u8> = (0..length).map(|i| ((i * 167 + 13) % 251) as u8).collect();
+        Storage::new_init_vec(&k, "ascii")
+    }
+
+    fn encrypt_lines() -> Vec<Storage> {
+        let k = keystream(100);
+        LINES
+            .iter()
+            .map(|l| xor_keystream(&Storage::new_init(l, "ascii"), &k))
+            .collect()
+    }
+
+    fn read_challenge6() -> Storage {
+        let contents = fixtures::bundled("set1challenge6.txt");
+        Storage::new_init(&contents.replace("\n", ""), "base64")
+    }
+
+    // TEST break_single_byte
+    #[test]
+    fn check_break_single_byte() {
+        let freq = helper::freq::get_char_freq_table();
+        let cipher = Storage::new_init(
+            "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736",
+            "hex",
+        );
+        let ans = break_single_byte(&cipher, &freq);
+        assert_eq!(b'X', ans.key);
+        assert_eq!("Cooking MC's like a pound of bacon", ans.plaintext.to_string());
+    }
+
+    #[test]
+    fn check_rank_single_byte() {
+        let freq = helper::freq::get_char_freq_table();
+        let cipher = Storage::new_init(
+            "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736",
+            "hex",
+        );
+        let ranked = rank_single_byte(&cipher, &freq);
+        assert_eq!(256, ranked.len());
+        assert_eq!(b'X', ranked[0].key);
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    // TEST detect_single_byte
+    #[test]
+    fn check_detect_single_byte() {
+        let contents = fixtures::bundled("set1challenge4.txt");
+        let lines: Vec<Storage> = contents.lines().map(|l| Storage::new_init(l, "hex")).collect();
+
+        let found = detect_single_byte(&lines, -4.0, 0);
+        assert_eq!(1, found.len());
+        assert_eq!(171, found[0].line);
+        assert_eq!(b'5', found[0].key);
+        assert_eq!("Now that the party is jumping\n", found[0].plaintext.to_string());
+    }
+
+    #[test]
+    fn check_detect_single_byte_threads_agree() {
+        let mut lines: Vec<Storage> = PLAINTEXT
+            .split(", ")
+            .enumerate()
+            .map(|(i, l)| encrypt(l, &((b'A' + i as u8) as char).to_string()))
+            .collect();
+        lines.insert(3, Storage::new_init("", "ascii"));
+        lines.push(Storage::new_init_vec(&(0u8..=255u8).rev().collect::<Vec<u8>>(), "ascii"));
+
+        let single = detect_single_byte(&lines, -4.0, 1);
+        let many = detect_single_byte(&lines, -4.0, 3);
+        assert_eq!(10, single.len());
+        assert_eq!(
+            single.iter().map(|d| (d.line, d.key)).collect::<Vec<_>>(),
+            many.iter().map(|d| (d.line, d.key)).collect::<Vec<_>>()
+        );
+        assert!(single.iter().all(|d| d.line != 4 && d.line != 12));
+        assert!(single.windows(2).all(|d| d[0].score >= d[1].score));
+    }
+
+    // TEST estimate_keysize
+    #[test]
+    fn check_estimate_keysize_all_methods() {
+        let cipher = encrypt(PLAINTEXT, "SECRET");
+        for method in [
+            KeySizeMethod::HammingDistance,
+            KeySizeMethod::IndexOfCoincidence,
+            KeySizeMethod::Autocorrelation,
+        ] {
+            let sizes = estimate_keysize(&cipher, 2, 20, method);
+            assert_eq!(19, sizes.len());
+            assert!(sizes.iter().take(3).any(|s| s.0 % 6 == 0), "{:?} missed 6", method);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_estimate_keysize_range() {
+        let cipher = encrypt(PLAINTEXT, "SECRET");
+        estimate_keysize(&cipher, 10, 2, KeySizeMethod::HammingDistance);
+    }
+
+    // TEST break_repeating_key
+    #[test]
+    fn check_break_repeating_key_short_ciphertext() {
+        let cipher = encrypt(PLAINTEXT, "SECRET");
+        let ans = break_repeating_key(&cipher, 2, 20, 3, KeySizeMethod::HammingDistance);
+        assert_eq!("SECRET", ans.key.to_string());
+        assert_eq!(6, ans.keysize);
+        assert_eq!(PLAINTEXT, ans.plaintext.to_string());
+        assert_eq!(1.0, ans.confidence);
+        assert_eq!(3, ans.keysizes.len());
+    }
+
+    #[test]
+    fn check_break_repeating_key_few_bytes_per_column() {
+        // 74 bytes, longer keys leave columns short enough to overfit
+        let plaintext = "Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal";
+        let cipher = encrypt(plaintext, "ICE");
+        for &(max, top_k) in &[(40, 40), (10, 10)] {
+            let ranked = rank_repeating_key(&cipher, 2, max, top_k, KeySizeMethod::HammingDistance);
+            assert_eq!("ICE", ranked[0].key.to_string());
+            assert_eq!(3, ranked[0].keysize);
+            assert_eq!(plaintext, ranked[0].plaintext.to_string());
+            assert!(ranked.iter().all(|c| c.keysize <= 74 / MIN_COLUMN));
+            assert_eq!(1, ranked.iter().filter(|c| c.key.to_string() == "ICE").count());
+        }
+    }
+
+    #[test]
+    fn check_key_period() {
+        assert_eq!(3, key_period(b"ICEICEICE"));
+        assert_eq!(9, key_period(b"ICEICBICE"));
+        assert_eq!(1, key_period(b"AAAA"));
+        assert_eq!(5, key_period(b"ABCAB"));
+    }
+
+    #[test]
+    fn check_break_repeating_key_challenge6() {
+        let cipher = read_challenge6();
+        for method in [
+            KeySizeMethod::HammingDistance,
+            KeySizeMethod::IndexOfCoincidence,
+            KeySizeMethod::Autocorrelation,
+        ] {
+            let ans = break_repeating_key(&cipher, 2, 40, 3, method);
+            assert_eq!("Terminator X: Bring the noise", ans.key.to_string());
+            assert_eq!(29, ans.keysize);
+        }
+    }
+
+    #[test]
+    fn check_rank_repeating_key() {
+        let cipher = read_challenge6();
+        let ranked = rank_repeating_key(&cipher, 2, 40, 3, KeySizeMethod::HammingDistance);
+        assert_eq!(3, ranked.len());
+        assert_eq!(29, ranked[0].keysize);
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    // TEST xor_repeating_key
+    #[test]
+    fn check_xor_repeating_key() {
+        let k = Storage::new_init("ICE", "ascii");
+        // the key is longer than the message
+        let short = xor_repeating_key(&Storage::new_init("hi", "ascii"), &k);
+        assert_eq!(&vec![b'h' ^ b'I', b'i' ^ b'C'], short.get_data());
+        assert_eq!(
+            encrypt(PLAINTEXT, "ICE").get_data(),
+            xor_repeating_key(&Storage::new_init(PLAINTEXT, "ascii"), &k).get_data()
+        );
+        assert_eq!(0, xor_repeating_key(&Storage::new_init("", "ascii"), &k).len());
+    }
+
+    #[test]
+    #[should_panic(expected = "empty key")]
+    fn check_invalid_xor_repeating_key_empty_key() {
+        xor_repeating_key(&Storage::new_init("hi", "ascii"), &Storage::new_init("", "ascii"));
+    }
+
+    // TEST xor_keystream
+    #[test]
+    fn check_xor_keystream() {
+        let k = Storage::new_init("abcdef", "ascii");
+        let ans = xor_keystream(&Storage::new_init("abc", "ascii"), &k);
+        assert_eq!(&vec![0, 0, 0], ans.get_data());
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_xor_keystream_too_short() {
+        let k = Storage::new_init("ab", "ascii");
+        xor_keystream(&Storage::new_init("abc", "ascii"), &k);
+    }
+
+    // TEST break_many_time_pad
+    #[test]
+    fn check_break_many_time_pad() {
+        let ciphertexts = encrypt_lines();
+        let ans = break_many_time_pad(&ciphertexts);
+        let k = keystream(100);
+
+        assert_eq!(77, ans.keystream.len());
+        assert_eq!(LINES.len(), ans.plaintexts.len());
+        assert_eq!(12, ans.samples[0]);
+        assert_eq!(1, ans.samples[76]);
+
+        // most positions covered by at least 6 ciphertexts are recovered
+        let covered: Vec<usize> = (0..ans.keystream.len()).filter(|i| ans.samples[*i] >= 6).collect();
+        let correct = covered
+            .iter()
+            .filter(|i| k.get_data()[**i] == ans.keystream.get_data()[**i])
+            .count();
+        assert!(correct * 10 >= covered.len() * 8, "{} / {}", correct, covered.len());
+    }
+
+    #[test]
+    fn check_break_many_time_pad_pinned() {
+        let ciphertexts = encrypt_lines();
+        let mut pins = HashMap::new();
+        pin_plaintext(
+            &mut pins,
+            &ciphertexts[11],
+            44,
+            &Storage::new_init("in general were settled for ever.", "ascii"),
+        );
+        let ans = break_many_time_pad_pinned(&ciphertexts, &pins);
+
+        assert_eq!(&LINES[11][44..], &ans.plaintexts[11].to_string()[44..]);
+        assert_eq!(keystream(77).get_data()[44..], ans.keystream.get_data()[44..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_pin_plaintext_past_end() {
+        let mut pins = HashMap::new();
+        let cipher = Storage::new_init("abc", "ascii");
+        pin_plaintext(&mut pins, &cipher, 2, &Storage::new_init("cd", "ascii"));
+    }
+
+    // TEST repeating_period
+    #[test]
+    fn check_repeating_period() {
+        let known = |s: &str| s.bytes().map(Some).collect::<Vec<Option<u8>>>();
+        assert_eq!(Some(3), repeating_period(&known("ICEICEI"), 10));
+        // a single byte that happens to repeat is not a period
+        assert_eq!(None, repeating_period(&known("ABCA"), 10));
+        assert_eq!(None, repeating_period(&known("ABCAB"), 10));
+        // 3 overlaps at period 4 (ICE?ICEI) are not enough either
+        let mut gaps = known("ICEICEI");
+        gaps.insert(3, None);
+        assert_eq!(None, repeating_period(&gaps, 10));
+        assert_eq!(Some(2), repeating_period(&known("xyxyxy"), 10));
+    }
+
+    // TEST recover_known_plaintext
+    #[test]
+    fn check_recover_known_plaintext_known_offset() {
+        let plaintext = "Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal";
+        let cipher = encrypt(plaintext, "ICE");
+        let fragments = vec![KnownPlaintext {
+            plaintext: Storage::new_init("Burning", "ascii"),
+            offset: Some(0),
+        }];
+        let ans = recover_known_plaintext(&cipher, &fragments, 10);
+
+        assert_eq!(Some(3), ans.period);
+        assert_eq!("ICE", ans.key.unwrap().to_string());
+        assert_eq!(cipher.len(), ans.keystream.known());
+        assert_eq!(vec![Some(0)], ans.offsets);
+    }
+
+    #[test]
+    fn check_recover_known_plaintext_unknown_offset() {
+        let cipher = encrypt(PLAINTEXT, "SECRETKEY");
+        let fragments = vec![KnownPlaintext {
+            plaintext: Storage::new_init("the season of Light", "ascii"),
+            offset: None,
+        }];
+        let ans = recover_known_plaintext(&cipher, &fragments, 12);
+
+        assert_eq!(Some(PLAINTEXT.find("the season of Light").unwrap()), ans.offsets[0]);
+        assert_eq!(Some(9), ans.period);
+        let key = ans.key.unwrap();
+        assert_eq!("SECRETKEY", key.to_string());
+        assert_eq!(PLAINTEXT, (&cipher ^ &key).to_string());
+    }
+
+    #[test]
+    fn check_recover_known_plaintext_partial_keystream() {
+        let key = "a much longer key than the fragment";
+        let cipher = encrypt(PLAINTEXT, key);
+        let fragments = vec![
+            KnownPlaintext {
+                plaintext: Storage::new_init("It was", "ascii"),
+                offset: Some(0),
+            },
+            KnownPlaintext {
+                plaintext: Storage::new_init("despair.", "ascii"),
+                offset: None,
+            },
+        ];
+        let ans = recover_known_plaintext(&cipher, &fragments, 8);
+
+        assert_eq!(None, ans.period);
+        assert!(ans.key.is_none());
+        assert_eq!(6, ans.keystream.known());
+        assert_eq!(Some(key.as_bytes()[1]), ans.keystream.get(1));
+        assert_eq!(None, ans.offsets[1]);
+    }
+}
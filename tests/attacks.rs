@@ -0,0 +1,56 @@
+extern crate cryptopals;
+
+use cryptopals::aes;
+use cryptopals::analysis;
+use cryptopals::challenge;
+use cryptopals::set1::helper::freq;
+use cryptopals::xor;
+use cryptopals::Storage;
+
+use std::fs;
+
+// TEST xor
+#[test]
+fn check_break_single_byte() {
+    let cipher = Storage::new_init(
+        "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736",
+        "hex",
+    );
+    let ans = xor::break_single_byte(&cipher, &freq::get_char_freq_table());
+    assert_eq!(b'X', ans.key);
+    assert_eq!("Cooking MC's like a pound of bacon", ans.plaintext.to_string());
+}
+
+#[test]
+fn check_break_repeating_key() {
+    let contents = fs::read_to_string("./input_files/set1challenge6.txt").unwrap();
+    let cipher = Storage::new_init(&contents.replace("\n", ""), "base64");
+    let ans = xor::break_repeating_key(&cipher, 2, 40, 3, xor::KeySizeMethod::HammingDistance);
+    assert_eq!("Terminator X: Bring the noise", ans.key.to_string());
+}
+
+// TEST aes
+#[test]
+fn check_detect_ecb() {
+    let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+    let plaintext = Storage::new_init(&"0123456789abcdef".repeat(3), "ascii");
+    let lines = vec![Storage::new_init("00112233445566778899aabbccddeeff", "hex"), aes::ecb_encrypt(&plaintext, &key)];
+    let ranked = aes::detect_ecb(&lines);
+    assert_eq!(2, ranked[0].line);
+    assert_eq!(2, ranked[0].repeats);
+}
+
+// TEST analysis
+#[test]
+fn check_entropy() {
+    assert_eq!(0.0, analysis::entropy(&Storage::new_init("aaaa", "ascii")));
+    assert_eq!(1.0, analysis::entropy(&Storage::new_init("abab", "ascii")));
+}
+
+// TEST challenge
+#[test]
+fn check_every_challenge_passes() {
+    for c in challenge::registry() {
+        assert!(c.verify().passed(), "{} failed", c.header());
+    }
+}
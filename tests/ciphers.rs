@@ -0,0 +1,48 @@
+extern crate cryptopals;
+
+use cryptopals::aes;
+use cryptopals::classical;
+use cryptopals::Storage;
+
+use std::fs;
+
+fn read_base64(filename: &str) -> Storage {
+    let contents = fs::read_to_string(filename).unwrap();
+    let mut s = Storage::new_init(&contents.replace("\n", ""), "base64");
+    s.change_base("ascii");
+    s
+}
+
+// TEST aes
+#[test]
+fn check_ecb_challenge7() {
+    let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+    let plaintext = aes::pkcs7_unpad(&aes::ecb_decrypt(&read_base64("./input_files/set1challenge7.txt"), &key), aes::BLOCK);
+    let expected = fs::read_to_string("./input_files/set1challenge7_answer.txt").unwrap();
+    assert_eq!(expected, plaintext.to_string());
+}
+
+#[test]
+fn check_modes_round_trip() {
+    let key = Storage::new_init("YELLOW SUBMARINE", "ascii");
+    let iv = Storage::new_init("00000000000000000000000000000000", "hex");
+    let message = Storage::new_init("the quick brown fox jumps over the lazy dog", "ascii");
+    let padded = aes::pkcs7_pad(&message, aes::BLOCK);
+
+    let ecb = aes::ecb_decrypt(&aes::ecb_encrypt(&padded, &key), &key);
+    assert_eq!(message.to_string(), aes::pkcs7_unpad(&ecb, aes::BLOCK).to_string());
+
+    let cbc = aes::cbc_decrypt(&aes::cbc_encrypt(&padded, &key, &iv), &key, &iv);
+    assert_eq!(message.to_string(), aes::pkcs7_unpad(&cbc, aes::BLOCK).to_string());
+
+    let ctr = aes::ctr(&aes::ctr(&message, &key, 7), &key, 7);
+    assert_eq!(message.to_string(), ctr.to_string());
+}
+
+// TEST classical
+#[test]
+fn check_vigenere_round_trip() {
+    let ciphertext = classical::vigenere_encrypt("Attack at dawn!", "LEMON");
+    assert_eq!("Lxfopv ef rnhr!", ciphertext);
+    assert_eq!("Attack at dawn!", classical::vigenere_decrypt(&ciphertext, "LEMON"));
+}
@@ -0,0 +1,34 @@
+extern crate cryptopals;
+
+use cryptopals::convert::{self, ConvertOptions};
+use cryptopals::Storage;
+
+// TEST Storage
+#[test]
+fn check_change_base() {
+    let mut s = Storage::new_init(
+        "49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d",
+        "hex",
+    );
+    s.change_base("base64");
+    assert_eq!("SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t", s.to_string());
+    s.change_base("ascii");
+    assert_eq!("I'm killing your brain like a poisonous mushroom", s.to_string());
+}
+
+#[test]
+fn check_fixed_xor() {
+    let l = Storage::new_init("1c0111001f010100061a024b53535009181c", "hex");
+    let r = Storage::new_init("686974207468652062756c6c277320657965", "hex");
+    assert_eq!("746865206b696420646f6e277420706c6179", (&l ^ &r).to_string());
+}
+
+// TEST convert
+#[test]
+fn check_convert() {
+    let mut out: Vec<u8> = Vec::new();
+    let options = ConvertOptions { wrap: 0, strict: true };
+    let n = convert::convert("YELLOW SUBMARINE".as_bytes(), &mut out, "ascii", "hex", &options);
+    assert_eq!(16, n);
+    assert_eq!("59454c4c4f57205355424d4152494e45\n", String::from_utf8(out).unwrap());
+}
@@ -0,0 +1,36 @@
+extern crate cryptopals;
+
+use cryptopals::fixtures;
+
+use std::env;
+use std::fs;
+use std::panic;
+
+// TEST set_dir, read, verify
+// One test so nothing else in this binary reads the fixtures while the directory is changed
+#[test]
+fn check_external_dir() {
+    let dir = env::temp_dir().join(format!("cryptopals-fixtures-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for f in fixtures::BUNDLED.iter() {
+        fs::write(dir.join(f.name), f.contents).unwrap();
+    }
+    fixtures::set_dir(dir.to_str());
+    assert!(fixtures::verify().iter().all(|c| c.passed()));
+    assert_eq!(fixtures::bundled("set1challenge7.txt"), fixtures::read("set1challenge7.txt"));
+
+    // a changed copy no longer matches the manifest
+    fs::write(dir.join("set1challenge4.txt"), "0000\n").unwrap();
+    let checks = fixtures::verify();
+    assert!(!checks[1].passed());
+    assert_eq!(dir.join("set1challenge4.txt").display().to_string(), checks[1].source);
+    assert!(panic::catch_unwind(|| fixtures::read("set1challenge4.txt")).is_err());
+
+    // a missing copy can't be read at all
+    fs::remove_file(dir.join("set1challenge6.txt")).unwrap();
+    assert_eq!(None, fixtures::verify()[2].actual);
+
+    fixtures::set_dir(None);
+    assert!(fixtures::verify().iter().all(|c| c.passed()));
+    fs::remove_dir_all(&dir).unwrap();
+}